@@ -1,18 +1,15 @@
 use anyhow::{Context, Result};
 use dialoguer::Confirm;
 use std::env;
-use std::path::PathBuf;
+use std::path::Path;
 use std::process::Command;
 
 use crate::config::Config;
+use crate::git::{is_git_repo, main_worktree};
 use crate::github::create_github_remote_if_possible;
 use crate::projects::{Project, ProjectStore};
 
-fn is_git_repo(path: &PathBuf) -> bool {
-    path.join(".git").exists()
-}
-
-fn git_init(path: &PathBuf) -> Result<bool> {
+fn git_init(path: &Path) -> Result<bool> {
     let output = Command::new("git")
         .arg("init")
         .current_dir(path)
@@ -42,26 +39,50 @@ pub fn run(tags: Option<String>) -> Result<()> {
         .map(|t| t.split(',').map(|s| s.trim().to_lowercase()).collect())
         .unwrap_or_default();
 
-    let project = Project::new(canonical_path.clone()).with_tags(tags_vec.clone());
+    add_project(&config, &mut store, &canonical_path, tags_vec, true)?;
+
+    store.save()?;
+    Ok(())
+}
+
+/// Track `canonical_path` with the given tags plus tags from config rules,
+/// detect its auto tags, worktrees and workspace members. When `init_repo` is
+/// set, a newly tracked project is offered git init and GitHub remote
+/// creation according to the config.
+pub fn add_project(
+    config: &Config,
+    store: &mut ProjectStore,
+    canonical_path: &Path,
+    tags_vec: Vec<String>,
+    init_repo: bool,
+) -> Result<()> {
+    let rule_tags: Vec<String> = config
+        .rule_tags(canonical_path)
+        .into_iter()
+        .filter(|t| !tags_vec.contains(t))
+        .collect();
+
+    let all_tags: Vec<String> = tags_vec.iter().chain(&rule_tags).cloned().collect();
+    let project = Project::new(canonical_path.to_path_buf()).with_tags(all_tags);
 
     let is_new = store.add(project);
 
     if is_new {
         eprintln!("Added: {}", canonical_path.display());
 
-        if config.git_init_on_add && !is_git_repo(&canonical_path) {
+        if init_repo && config.git_init_on_add && !is_git_repo(canonical_path) {
             let should_init = Confirm::new()
                 .with_prompt("Initialize git repository?")
                 .default(true)
                 .interact()?;
 
-            if should_init && git_init(&canonical_path)? {
+            if should_init && git_init(canonical_path)? {
                 eprintln!("Initialized git repository");
             }
         }
 
-        if config.gh_create_on_add && is_git_repo(&canonical_path) {
-            create_github_remote_if_possible(&canonical_path)?;
+        if init_repo && config.gh_create_on_add && is_git_repo(canonical_path) {
+            create_github_remote_if_possible(canonical_path)?;
         }
     } else {
         eprintln!(
@@ -74,6 +95,35 @@ pub fn run(tags: Option<String>) -> Result<()> {
         eprintln!("Tags: {}", tags_vec.join(", "));
     }
 
-    store.save()?;
+    if !rule_tags.is_empty() {
+        eprintln!("Tags from rules: {}", rule_tags.join(", "));
+    }
+
+    if let Some(project) = store.find_by_path_mut(canonical_path) {
+        project.refresh_auto_tags();
+        if !project.auto_tags.is_empty() {
+            eprintln!("Detected: {}", project.auto_tags.join(", "));
+        }
+    }
+
+    match store.sync_members(config, canonical_path) {
+        Ok((added, _)) if added > 0 => eprintln!("Tracked {} workspace members", added),
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: failed to read workspace members: {}", e),
+    }
+
+    if is_git_repo(canonical_path) {
+        // A linked worktree is tracked under its main worktree when that is tracked
+        let repo = main_worktree(canonical_path)
+            .ok()
+            .filter(|repo| store.find_by_path(repo).is_some())
+            .unwrap_or_else(|| canonical_path.to_path_buf());
+        match store.sync_worktrees(config, &repo) {
+            Ok((added, _)) if added > 0 => eprintln!("Tracked {} worktrees", added),
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: failed to list worktrees: {}", e),
+        }
+    }
+
     Ok(())
 }
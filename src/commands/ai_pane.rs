@@ -0,0 +1,37 @@
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::path::PathBuf;
+
+use crate::projects::ProjectStore;
+
+/// Set whether the AI assistant opens in its own multiplexer pane for one
+/// project (`on`, `off`, or `default` to follow the `ai_pane` setting). The
+/// project is the tracked one containing `path` or the current directory.
+pub fn set(value: &str, path: Option<PathBuf>) -> Result<()> {
+    let setting = match value {
+        "on" => Some(true),
+        "off" => Some(false),
+        "default" => None,
+        _ => bail!("Invalid AI pane setting '{}' (expected on, off or default)", value),
+    };
+
+    let mut store = ProjectStore::load()?;
+
+    let path = match path {
+        Some(path) => path,
+        None => env::current_dir().context("Failed to get current directory")?,
+    };
+    let path = path.canonicalize().unwrap_or(path);
+    let project_path = store
+        .find_entry_containing(&path)
+        .map(|p| p.path.clone())
+        .with_context(|| format!("Not inside a tracked project: {}", path.display()))?;
+
+    if let Some(project) = store.find_by_path_mut(&project_path) {
+        project.ai_pane = setting;
+    }
+    store.save()?;
+
+    eprintln!("AI pane {}: {}", value, project_path.display());
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::commands::add::add_project;
+use crate::commands::select::{open_project, LaunchOverrides};
+use crate::config::Config;
+use crate::directive;
+use crate::git::is_git_repo;
+use crate::glob::expand_tilde;
+use crate::projects::ProjectStore;
+
+/// Host, owner and repository name extracted from a clone URL
+#[derive(Debug)]
+struct RepoUrl {
+    host: String,
+    owner: String,
+    repo: String,
+}
+
+/// Parse `https://host/owner/repo(.git)`, `ssh://user@host:port/owner/repo`,
+/// scp-style `user@host:owner/repo`, `file:///path/repo` and local paths.
+/// Local repositories use `local` as host and their parent directory as owner.
+fn parse_url(url: &str) -> Result<RepoUrl> {
+    let url = url.trim().trim_end_matches('/');
+
+    let (host, path) = if let Some(path) = url.strip_prefix("file://") {
+        ("local".to_string(), path.to_string())
+    } else if let Some((_, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        let host = host.split(':').next().unwrap_or(host);
+        (host.to_string(), path.to_string())
+    } else if url.starts_with(['/', '.', '~']) {
+        ("local".to_string(), url.to_string())
+    } else if let Some((authority, path)) = url.split_once(':') {
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        (host.to_string(), path.to_string())
+    } else {
+        bail!("Unrecognized repository URL: {}", url);
+    };
+
+    let mut components: Vec<&str> = path
+        .split('/')
+        .filter(|c| !c.is_empty() && *c != "." && *c != "..")
+        .collect();
+    let repo = components
+        .pop()
+        .map(|r| r.trim_end_matches(".git").to_string())
+        .filter(|r| !r.is_empty())
+        .with_context(|| format!("No repository name in URL: {}", url))?;
+
+    let owner = if host == "local" {
+        components.last().map(|c| c.to_string()).unwrap_or_default()
+    } else {
+        components.join("/")
+    };
+
+    Ok(RepoUrl { host, owner, repo })
+}
+
+/// Destination directory for a repository according to the config layout
+fn destination(config: &Config, repo_url: &RepoUrl) -> PathBuf {
+    let relative = config
+        .clone_layout
+        .replace("{host}", &repo_url.host)
+        .replace("{owner}", &repo_url.owner)
+        .replace("{repo}", &repo_url.repo);
+    let relative: Vec<&str> = relative.split('/').filter(|c| !c.is_empty()).collect();
+    PathBuf::from(expand_tilde(&config.clone_root)).join(relative.join("/"))
+}
+
+pub fn run(
+    url: &str,
+    tags: Option<String>,
+    open: bool,
+    overrides: LaunchOverrides,
+) -> Result<()> {
+    if open {
+        directive::check_shell()?;
+    }
+    let config = Config::load()?;
+    let mut store = ProjectStore::load()?;
+
+    let repo_url = parse_url(url)?;
+    let dest = destination(&config, &repo_url);
+
+    if is_git_repo(&dest) {
+        eprintln!("Already cloned: {}", dest.display());
+    } else {
+        if dest.exists() && fs::read_dir(&dest).map(|mut d| d.next().is_some())? {
+            bail!("Destination exists and is not empty: {}", dest.display());
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        eprintln!("Cloning {} into {}", url, dest.display());
+        // git's stdout goes to stderr so the shell function only sees pj's output
+        let status = Command::new("git")
+            .arg("clone")
+            .arg(url)
+            .arg(&dest)
+            .stdout(Stdio::from(io::stderr()))
+            .status()
+            .context("Failed to run git clone")?;
+        if !status.success() {
+            bail!("git clone failed for {}", url);
+        }
+    }
+
+    let canonical_path = dest
+        .canonicalize()
+        .context("Failed to resolve canonical path")?;
+
+    let tags_vec: Vec<String> = tags
+        .map(|t| t.split(',').map(|s| s.trim().to_lowercase()).collect())
+        .unwrap_or_default();
+
+    add_project(&config, &mut store, &canonical_path, tags_vec, false)?;
+    store.save()?;
+
+    if open {
+        open_project(&config, &mut store, &canonical_path, overrides)?;
+    }
+    Ok(())
+}
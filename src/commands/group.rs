@@ -0,0 +1,252 @@
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tabled::{Table, Tabled};
+
+use crate::commands::profile;
+use crate::commands::select::{open_ai, run_in_shell, LaunchOverrides};
+use crate::config::Config;
+use crate::directive;
+use crate::launch::CommandTemplate;
+use crate::multiplexer;
+use crate::projects::{Group, ProjectStore};
+use crate::tui::{select_projects_multi, SelectionResult};
+
+/// Editors that open a multi-root `.code-workspace` file
+const WORKSPACE_EDITORS: &[&str] = &["code", "code-insiders", "codium", "cursor", "windsurf"];
+
+#[derive(Tabled)]
+struct GroupRow {
+    #[tabled(rename = "GROUP")]
+    name: String,
+    #[tabled(rename = "PROJECTS")]
+    projects: String,
+    #[tabled(rename = "CD")]
+    cd: String,
+}
+
+/// Add projects to a group, creating it if needed. Without paths, the
+/// members are picked from the tracked projects.
+pub fn add(name: &str, paths: &[String]) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Group name cannot be empty");
+    }
+
+    let config = Config::load()?;
+    let mut store = ProjectStore::load()?;
+
+    let members: Vec<PathBuf> = if paths.is_empty() {
+        let projects = store.sorted_by_frecency();
+        if projects.is_empty() {
+            bail!("No projects tracked. Add a project with: pj -a");
+        }
+        match select_projects_multi(&projects, &config)? {
+            SelectionResult::MultiSelected(paths) => paths,
+            _ => {
+                eprintln!("Cancelled");
+                return Ok(());
+            }
+        }
+    } else {
+        let mut members = Vec::new();
+        for path in paths {
+            let resolved = if path == "." {
+                env::current_dir()?
+            } else {
+                PathBuf::from(path)
+            };
+            let resolved = resolved.canonicalize().unwrap_or(resolved);
+            if store.find_by_path(&resolved).is_none() {
+                bail!(
+                    "Not a tracked project: {} (add it with: pj -a {})",
+                    resolved.display(),
+                    resolved.display()
+                );
+            }
+            members.push(resolved);
+        }
+        members
+    };
+
+    let added = store.add_to_group(name, &members);
+    store.save()?;
+    eprintln!("Added {} projects to group '{}'", added, name);
+    Ok(())
+}
+
+/// Set the directory the shell changes into when opening a group: `path`, or
+/// the current directory
+pub fn set_cd(name: &str, path: Option<&str>) -> Result<()> {
+    let mut store = ProjectStore::load()?;
+
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => env::current_dir().context("Failed to get current directory")?,
+    };
+    if !path.is_dir() {
+        bail!("Not a directory: {}", path.display());
+    }
+    let path = path.canonicalize().unwrap_or(path);
+
+    if !store.set_group_cd(name, path.clone()) {
+        bail!("No group named '{}'", name);
+    }
+    store.save()?;
+    eprintln!("Group '{}' changes into {}", name, path.display());
+    Ok(())
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    let mut store = ProjectStore::load()?;
+    if !store.remove_group(name) {
+        bail!("No group named '{}'", name);
+    }
+    store.save()?;
+    eprintln!("Removed group '{}'", name);
+    Ok(())
+}
+
+pub fn list() -> Result<()> {
+    let store = ProjectStore::load()?;
+    if store.groups().is_empty() {
+        eprintln!("No groups found. Create one with: pj --group-add <NAME>");
+        return Ok(());
+    }
+
+    let rows: Vec<GroupRow> = store
+        .groups()
+        .iter()
+        .map(|g| GroupRow {
+            name: g.name.clone(),
+            projects: g
+                .projects
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            cd: g.cd_target().display().to_string(),
+        })
+        .collect();
+
+    println!("{}", Table::new(rows));
+    Ok(())
+}
+
+/// Open every member of a group: a generated VS Code workspace or one
+/// multiplexer window per member for the editor, then cd and the AI
+/// assistant in the group's shared directory
+pub fn open(
+    config: &Config,
+    store: &mut ProjectStore,
+    name: &str,
+    overrides: LaunchOverrides,
+) -> Result<()> {
+    let group = store
+        .find_group(name)
+        .cloned()
+        .with_context(|| format!("No group named '{}'", name))?;
+
+    let mut members = Vec::new();
+    for path in &group.projects {
+        if path.exists() {
+            members.push(path.clone());
+        } else {
+            eprintln!("Skipping missing project: {}", path.display());
+        }
+    }
+    if members.is_empty() {
+        bail!("Group '{}' has no existing projects", name);
+    }
+
+    for path in &members {
+        if let Some(project) = store.find_by_path_mut(path) {
+            project.update_access();
+        }
+    }
+    store.save()?;
+
+    // The shell changes directory before running the editor and AI assistant
+    let cd_target = group.cd_target();
+    if overrides.cd(config) {
+        directive::cd(&cd_target)?;
+    }
+
+    if let Some(profile_name) = &overrides.profile {
+        let profile_name = profile::choose(config, Some(profile_name))?;
+        profile::run(config, store, &profile_name, &members)?;
+    } else if let Some(editor) = overrides.editor(config)? {
+        if opens_workspaces(editor.program()) {
+            let workspace = write_code_workspace(&group, &members)?;
+            editor.spawn_editor(&workspace)?;
+        } else {
+            open_members(config, &editor, &members)?;
+        }
+    }
+
+    if let Some(ai) = overrides.ai(config)?.filter(|_| overrides.profile.is_none()) {
+        open_ai(config, &cd_target, &ai, overrides.ai_pane(config, None))?;
+    }
+
+    Ok(())
+}
+
+fn opens_workspaces(editor: &str) -> bool {
+    Path::new(editor)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| WORKSPACE_EDITORS.contains(&name))
+        .unwrap_or(false)
+}
+
+/// GUI editors are launched once per member; terminal editors get a
+/// multiplexer window per member, or run in the foreground for the first
+/// member outside a multiplexer
+fn open_members(config: &Config, editor: &CommandTemplate, members: &[PathBuf]) -> Result<()> {
+    if !editor.is_terminal(&config.terminal_editors) {
+        for path in members {
+            editor.spawn_editor(path)?;
+        }
+        return Ok(());
+    }
+
+    for (index, path) in members.iter().enumerate() {
+        let line = editor.editor_line(path);
+        if multiplexer::try_open_in_window(config, &line, path) {
+            continue;
+        }
+        if index > 0 {
+            // Printed by the shell once the editor exits
+            directive::echo(&format!(
+                "Not in a multiplexer: opened {} for {} only",
+                editor.program(),
+                members[0].display()
+            ))?;
+            break;
+        }
+        run_in_shell(&line)?;
+    }
+    Ok(())
+}
+
+/// Write `~/.pj/workspaces/<group>.code-workspace` listing the members
+fn write_code_workspace(group: &Group, members: &[PathBuf]) -> Result<PathBuf> {
+    let dir = Config::workspaces_dir()?;
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let folders: Vec<serde_json::Value> = members
+        .iter()
+        .map(|path| serde_json::json!({ "path": path }))
+        .collect();
+    let workspace = serde_json::json!({ "folders": folders, "settings": {} });
+
+    let file_name = group.name.replace(['/', '\\'], "-");
+    let path = dir.join(format!("{}.code-workspace", file_name));
+    let content =
+        serde_json::to_string_pretty(&workspace).context("Failed to serialize workspace")?;
+    fs::write(&path, content)
+        .with_context(|| format!("Failed to write workspace file: {}", path.display()))?;
+    Ok(path)
+}
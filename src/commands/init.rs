@@ -3,6 +3,7 @@ use dialoguer::{Input, Select};
 
 use crate::config::Config;
 use crate::github::is_gh_installed;
+use crate::launch;
 use crate::shell::Shell;
 
 pub fn run() -> Result<()> {
@@ -31,7 +32,10 @@ pub fn run() -> Result<()> {
 
     let editor = if editor_selection == editor_options.len() - 1 {
         Input::<String>::new()
-            .with_prompt("Enter your editor command")
+            .with_prompt("Enter your editor command ({path} marks where the path goes)")
+            .validate_with(|input: &String| {
+                launch::validate("editor", input).map_err(|e| format!("{:#}", e))
+            })
             .interact_text()?
     } else {
         editor_options[editor_selection].to_string()
@@ -64,7 +68,7 @@ pub fn run() -> Result<()> {
     };
 
     let should_ask_github =
-        git_init_on_add || existing_config.as_ref().map_or(false, |c| c.git_init_on_add);
+        git_init_on_add || existing_config.as_ref().is_some_and(|c| c.git_init_on_add);
 
     let gh_create_on_add = if should_ask_github {
         let gh_hint = if is_gh_installed() {
@@ -111,6 +115,9 @@ pub fn run() -> Result<()> {
     let ai_assistant = if ai_selection == ai_options.len() - 1 {
         Input::<String>::new()
             .with_prompt("Enter your AI assistant command")
+            .validate_with(|input: &String| {
+                launch::validate("AI assistant", input).map_err(|e| format!("{:#}", e))
+            })
             .interact_text()?
     } else {
         ai_options[ai_selection].to_string()
@@ -122,8 +129,10 @@ pub fn run() -> Result<()> {
         git_init_on_add,
         gh_create_on_add,
         ai_assistant,
+        ..existing_config.unwrap_or_default()
     };
 
+    config.validate_commands()?;
     config.save()?;
     eprintln!(
         "Configuration saved to {}",
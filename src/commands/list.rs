@@ -1,11 +1,18 @@
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use chrono::{DateTime, Local, Utc};
 use crossterm::terminal;
+use tabled::settings::location::ByColumnName;
+use tabled::settings::Remove;
 use tabled::{Table, Tabled};
 
-use crate::projects::ProjectStore;
+use crate::config::Config;
+use crate::git::{self, RepoStatus};
+use crate::projects::{Project, ProjectStore};
+
+const GIT_COLUMNS: [&str; 3] = ["BRANCH", "GIT", "LAST COMMIT"];
 
 #[derive(Tabled)]
 struct ProjectRow {
@@ -19,6 +26,12 @@ struct ProjectRow {
     last_accessed: String,
     #[tabled(rename = "STATUS")]
     status: String,
+    #[tabled(rename = "BRANCH")]
+    branch: String,
+    #[tabled(rename = "GIT")]
+    git: String,
+    #[tabled(rename = "LAST COMMIT")]
+    last_commit: String,
 }
 
 fn shorten_path(path: &Path, max_width: usize) -> String {
@@ -53,7 +66,8 @@ fn shorten_path(path: &Path, max_width: usize) -> String {
     full
 }
 
-pub fn run() -> Result<()> {
+pub fn run(group_by: Option<String>, git_status: bool) -> Result<()> {
+    let config = Config::load()?;
     let store = ProjectStore::load()?;
 
     let projects = store.sorted_by_frecency();
@@ -65,7 +79,7 @@ pub fn run() -> Result<()> {
 
     let tags_width = projects
         .iter()
-        .map(|p| p.tags.join(", ").len())
+        .map(|p| p.tags_display().len())
         .max()
         .unwrap_or(0)
         .max(4);
@@ -77,23 +91,74 @@ pub fn run() -> Result<()> {
         .unwrap_or(0)
         .max(6);
 
+    let statuses = if git_status || config.git_status {
+        let paths: Vec<PathBuf> = projects.iter().map(|p| p.path.clone()).collect();
+        Some(git::repo_statuses(&paths, config.git_status_timeout()))
+    } else {
+        None
+    };
+
     // 6 borders + 5 columns * 2 padding + fixed columns (LAST ACCESSED=16, STATUS=7)
-    let overhead = 16 + tags_width + access_width + 16 + 7;
+    let mut overhead = 16 + tags_width + access_width + 16 + 7;
+    if let Some(statuses) = &statuses {
+        let branch_width = statuses.values().map(|s| s.branch.len()).max().unwrap_or(0);
+        // 3 more borders and paddings + GIT (~13) and LAST COMMIT (11) columns
+        overhead += 9 + branch_width.max(6) + 13 + 11;
+    }
 
     let max_path_width = terminal::size()
         .map(|(w, _)| (w as usize).saturating_sub(overhead))
         .unwrap_or(50);
 
+    let Some(key) = group_by else {
+        print_table(&projects, max_path_width, statuses.as_ref());
+        return Ok(());
+    };
+
+    // Group by the values of a `key=value` tag; a project with several
+    // values for the key appears in each group
+    let key = key.to_lowercase();
+    let mut groups: BTreeMap<String, Vec<&Project>> = BTreeMap::new();
+    let mut ungrouped: Vec<&Project> = Vec::new();
+    for project in &projects {
+        let values = project.tag_values(&key);
+        if values.is_empty() {
+            ungrouped.push(project);
+        }
+        for value in values {
+            groups.entry(value.to_string()).or_default().push(project);
+        }
+    }
+
+    for (value, members) in &groups {
+        println!("{}={}", key, value);
+        print_table(members, max_path_width, statuses.as_ref());
+        println!();
+    }
+    if !ungrouped.is_empty() {
+        println!("(no {})", key);
+        print_table(&ungrouped, max_path_width, statuses.as_ref());
+    }
+
+    Ok(())
+}
+
+fn print_table(
+    projects: &[&Project],
+    max_path_width: usize,
+    statuses: Option<&HashMap<PathBuf, RepoStatus>>,
+) {
     let rows: Vec<ProjectRow> = projects
         .iter()
         .map(|p| {
             let dt = DateTime::<Utc>::from_timestamp(p.last_accessed, 0)
                 .map(|dt| dt.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
                 .unwrap_or_else(|| "Unknown".to_string());
+            let git = statuses.and_then(|s| s.get(&p.path));
 
             ProjectRow {
                 path: shorten_path(&p.path, max_path_width),
-                tags: p.tags.join(", "),
+                tags: p.tags_display(),
                 access_count: p.access_count,
                 last_accessed: dt,
                 status: if p.exists() {
@@ -101,12 +166,20 @@ pub fn run() -> Result<()> {
                 } else {
                     "MISSING".to_string()
                 },
+                branch: git.map(|g| g.branch.clone()).unwrap_or_else(|| "-".to_string()),
+                git: git.map(|g| g.state()).unwrap_or_else(|| "-".to_string()),
+                last_commit: git
+                    .map(|g| g.last_commit_age())
+                    .unwrap_or_else(|| "-".to_string()),
             }
         })
         .collect();
 
-    let table = Table::new(rows);
+    let mut table = Table::new(rows);
+    if statuses.is_none() {
+        for column in GIT_COLUMNS {
+            table.with(Remove::column(ByColumnName::new(column)));
+        }
+    }
     println!("{}", table);
-
-    Ok(())
 }
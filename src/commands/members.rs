@@ -0,0 +1,34 @@
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::projects::ProjectStore;
+
+/// Discover the workspace members of every tracked project
+pub fn sync() -> Result<()> {
+    let config = Config::load()?;
+    let mut store = ProjectStore::load()?;
+
+    let roots: Vec<_> = store
+        .sorted_by_frecency()
+        .into_iter()
+        .filter(|p| p.parent.is_none() && p.exists())
+        .map(|p| p.path.clone())
+        .collect();
+
+    let (mut added, mut removed) = (0, 0);
+    for root in &roots {
+        match store.sync_members(&config, root) {
+            Ok((a, r)) => {
+                added += a;
+                removed += r;
+            }
+            Err(e) => eprintln!("Warning: {}: {}", root.display(), e),
+        }
+    }
+
+    eprintln!("Workspace members: {} added, {} removed", added, removed);
+    if added > 0 || removed > 0 {
+        store.save()?;
+    }
+    Ok(())
+}
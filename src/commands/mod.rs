@@ -1,7 +1,15 @@
 pub mod add;
+pub mod ai_pane;
+pub mod clone;
+pub mod group;
 pub mod init;
 pub mod list;
+pub mod members;
+pub mod new;
+pub mod print;
+pub mod profile;
 pub mod rm;
 pub mod select;
 pub mod show_config;
 pub mod tag;
+pub mod worktree;
@@ -0,0 +1,209 @@
+use anyhow::{bail, Context, Result};
+use dialoguer::console::Term;
+use dialoguer::Select;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::commands::add::add_project;
+use crate::commands::select::{open_project, LaunchOverrides};
+use crate::config::{Config, Template};
+use crate::directive;
+use crate::glob::expand_tilde;
+use crate::projects::ProjectStore;
+
+/// Replace `{{key}}` (or `{{ key }}`) with its value; unknown keys are kept
+fn substitute(text: &str, vars: &BTreeMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let key = rest[start + 2..start + 2 + len].trim();
+        result.push_str(&rest[..start]);
+        match vars.get(key) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..start + len + 4]),
+        }
+        rest = &rest[start + len + 4..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn copy_dir(src: &Path, dest: &Path) -> Result<()> {
+    fs::create_dir_all(dest)
+        .with_context(|| format!("Failed to create directory: {}", dest.display()))?;
+    for entry in fs::read_dir(src)
+        .with_context(|| format!("Failed to read template: {}", src.display()))?
+    {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let target = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Substitute variables in file names and UTF-8 file contents under `dir`
+fn render(dir: &Path, vars: &BTreeMap<String, String>) -> Result<()> {
+    // Collect first: renaming entries while reading the directory may skip
+    // or revisit them
+    let entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    for entry in entries {
+        let mut path = entry.path();
+
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let rendered_name = substitute(&name, vars);
+        if rendered_name != name {
+            let renamed = dir.join(&rendered_name);
+            fs::rename(&path, &renamed)
+                .with_context(|| format!("Failed to rename {}", path.display()))?;
+            path = renamed;
+        }
+
+        if entry.file_type()?.is_dir() {
+            render(&path, vars)?;
+        } else if let Ok(content) = fs::read_to_string(&path) {
+            let rendered = substitute(&content, vars);
+            if rendered != content {
+                fs::write(&path, rendered)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fill `dest` from a template source: a local directory is copied, anything
+/// else is cloned as a git repository. The template's git history is dropped.
+fn instantiate(source: &str, dest: &Path) -> Result<()> {
+    let local = Path::new(&expand_tilde(source)).to_path_buf();
+    if local.is_dir() {
+        return copy_dir(&local, dest);
+    }
+
+    let status = Command::new("git")
+        .args(["clone", "--depth", "1", source])
+        .arg(dest)
+        .stdout(Stdio::from(io::stderr()))
+        .status()
+        .context("Failed to run git clone")?;
+    if !status.success() {
+        bail!("Failed to clone template from {}", source);
+    }
+    fs::remove_dir_all(dest.join(".git")).context("Failed to remove template git history")?;
+    Ok(())
+}
+
+fn choose_template(config: &Config, name: Option<&str>) -> Result<(String, Template)> {
+    if config.templates.is_empty() {
+        bail!(
+            "No templates configured. Add a [templates.<name>] table to {}",
+            Config::config_path()?.display()
+        );
+    }
+
+    if let Some(name) = name {
+        let template = config
+            .templates
+            .get(name)
+            .with_context(|| format!("Unknown template: {}", name))?;
+        return Ok((name.to_string(), template.clone()));
+    }
+
+    let names: Vec<&String> = config.templates.keys().collect();
+    let selection = if names.len() == 1 {
+        0
+    } else {
+        match Select::new()
+            .with_prompt("Which template? (Esc to cancel)")
+            .items(&names)
+            .default(0)
+            .interact_on_opt(&Term::stderr())?
+        {
+            Some(sel) => sel,
+            None => std::process::exit(130),
+        }
+    };
+    let name = names[selection].clone();
+    let template = config.templates[&name].clone();
+    Ok((name, template))
+}
+
+pub fn run(
+    name: &str,
+    template_name: Option<&str>,
+    tags: Option<String>,
+    overrides: LaunchOverrides,
+) -> Result<()> {
+    directive::check_shell()?;
+    let config = Config::load()?;
+    let mut store = ProjectStore::load()?;
+
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        bail!("Invalid project name: {}", name);
+    }
+
+    let (template_name, template) = choose_template(&config, template_name)?;
+
+    let root = match &config.new_root {
+        Some(root) => Path::new(&expand_tilde(root)).to_path_buf(),
+        None => env::current_dir().context("Failed to get current directory")?,
+    };
+    let dest = root.join(name);
+    if dest.exists() {
+        bail!("Path already exists: {}", dest.display());
+    }
+    fs::create_dir_all(&root)
+        .with_context(|| format!("Failed to create directory: {}", root.display()))?;
+
+    eprintln!("Creating {} from template '{}'", dest.display(), template_name);
+    instantiate(&template.source, &dest)?;
+
+    let mut vars = template.vars.clone();
+    vars.insert("name".to_string(), name.to_string());
+    render(&dest, &vars)?;
+
+    let shell_vars: BTreeMap<String, String> = vars
+        .iter()
+        .map(|(key, value)| (key.clone(), shell_words::quote(value).into_owned()))
+        .collect();
+    for command in &template.post_create {
+        let command = &substitute(command, &shell_vars);
+        eprintln!("Running: {}", command);
+        let status = directive::isolate(&mut Command::new("sh"))
+            .args(["-c", command])
+            .current_dir(&dest)
+            .stdout(Stdio::from(io::stderr()))
+            .status()
+            .with_context(|| format!("Failed to run '{}'", command))?;
+        if !status.success() {
+            eprintln!("Warning: '{}' exited with {}", command, status);
+        }
+    }
+
+    let canonical_path = dest
+        .canonicalize()
+        .context("Failed to resolve canonical path")?;
+
+    let tags_vec: Vec<String> = tags
+        .map(|t| t.split(',').map(|s| s.trim().to_lowercase()).collect())
+        .unwrap_or_default();
+
+    add_project(&config, &mut store, &canonical_path, tags_vec, true)?;
+    store.save()?;
+
+    open_project(&config, &mut store, &canonical_path, overrides)
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+
+use crate::commands::select::single_match;
+use crate::projects::ProjectStore;
+use crate::tui::filter_projects;
+
+/// Exit code when no existing project matches the filter terms
+pub const EXIT_NO_MATCH: i32 = 3;
+
+/// Print matching project paths in ranked order without a TUI, editor or
+/// store update. Prints only the best match unless `all` is set.
+pub fn run(filters: Vec<String>, all: bool) -> Result<()> {
+    let store = ProjectStore::load()?;
+
+    let projects = store.sorted_by_frecency();
+    let matches: Vec<_> = filter_projects(&projects, &filters)
+        .into_iter()
+        .filter(|p| p.exists())
+        .collect();
+
+    if matches.is_empty() {
+        eprintln!("No matching projects");
+        std::process::exit(EXIT_NO_MATCH);
+    }
+
+    if all {
+        for project in &matches {
+            println!("{}", project.path.display());
+        }
+    } else {
+        let best = single_match(&matches).unwrap_or(matches[0]);
+        println!("{}", best.path.display());
+    }
+
+    Ok(())
+}
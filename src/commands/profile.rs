@@ -0,0 +1,91 @@
+use anyhow::{bail, Context, Result};
+use dialoguer::console::Term;
+use dialoguer::Select;
+use std::path::{Path, PathBuf};
+
+use crate::commands::select::{run_in_shell, shell_escape};
+use crate::config::Config;
+use crate::directive;
+use crate::multiplexer;
+use crate::projects::ProjectStore;
+
+/// Look up a launch profile by name, or ask which one to run when no name is
+/// given and several are configured
+pub fn choose(config: &Config, name: Option<&str>) -> Result<String> {
+    if config.profiles.is_empty() {
+        bail!(
+            "No launch profiles configured. Add a [profiles] table to {}",
+            Config::config_path()?.display()
+        );
+    }
+
+    if let Some(name) = name {
+        if !config.profiles.contains_key(name) {
+            let known: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+            bail!("Unknown profile: {} (configured: {})", name, known.join(", "));
+        }
+        return Ok(name.to_string());
+    }
+
+    let names: Vec<&String> = config.profiles.keys().collect();
+    let selection = if names.len() == 1 {
+        0
+    } else {
+        match Select::new()
+            .with_prompt("Which profile? (Esc to cancel)")
+            .items(&names)
+            .default(0)
+            .interact_on_opt(&Term::stderr())?
+        {
+            Some(sel) => sel,
+            None => std::process::exit(130),
+        }
+    };
+    Ok(names[selection].clone())
+}
+
+/// Substitute `{path}`, `{name}` and `{tags}` (shell-quoted) in a profile
+/// command
+fn expand(command: &str, store: &ProjectStore, path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tags = store
+        .find_by_path(path)
+        .map(|p| p.effective_tags().join(","))
+        .unwrap_or_default();
+
+    command
+        .replace("{path}", &shell_escape(path))
+        .replace("{name}", &shell_words::quote(&name))
+        .replace("{tags}", &shell_words::quote(&tags))
+}
+
+/// Run a profile in each project: in new multiplexer windows when available,
+/// otherwise in the foreground for the first project only
+pub fn run(config: &Config, store: &ProjectStore, name: &str, paths: &[PathBuf]) -> Result<()> {
+    let template = config
+        .profiles
+        .get(name)
+        .with_context(|| format!("Unknown profile: {}", name))?;
+
+    for (index, path) in paths.iter().enumerate() {
+        let command = expand(template, store, path);
+        if multiplexer::try_open_in_window(config, &command, path) {
+            continue;
+        }
+        if index > 0 {
+            // Printed by the shell once the profile command exits
+            directive::echo(&format!(
+                "Not in a multiplexer: ran profile '{}' for {} only",
+                name,
+                paths[0].display()
+            ))?;
+            break;
+        }
+        // The shell function runs it, with the user's aliases and functions
+        run_in_shell(&format!("cd {} && {}", shell_escape(path), command))?;
+    }
+    Ok(())
+}
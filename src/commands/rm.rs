@@ -1,5 +1,6 @@
 use anyhow::{bail, Result};
 
+use crate::config::Config;
 use crate::projects::ProjectStore;
 use crate::tui::{select_projects_multi, SelectionResult};
 
@@ -22,7 +23,7 @@ pub fn run(missing: bool) -> Result<()> {
         bail!("No projects tracked. Add a project with: pj -a");
     }
 
-    match select_projects_multi(&projects)? {
+    match select_projects_multi(&projects, &Config::load()?)? {
         SelectionResult::MultiSelected(paths) => {
             let mut removed_count = 0;
             for path in &paths {
@@ -35,7 +36,9 @@ pub fn run(missing: bool) -> Result<()> {
                 store.save()?;
             }
         }
-        SelectionResult::Selected(path) | SelectionResult::MissingSelected(path) => {
+        SelectionResult::Selected(path)
+        | SelectionResult::MissingSelected(path)
+        | SelectionResult::Action(_, path) => {
             if store.remove(&path) {
                 eprintln!("Removed: {}", path.display());
                 store.save()?;
@@ -43,7 +46,7 @@ pub fn run(missing: bool) -> Result<()> {
                 eprintln!("Project not found: {}", path.display());
             }
         }
-        SelectionResult::Cancelled => {
+        SelectionResult::Cancelled | SelectionResult::Group(_) => {
             std::process::exit(130);
         }
     }
@@ -1,33 +1,204 @@
 use anyhow::{bail, Result};
 use dialoguer::Confirm;
-use std::process::Command;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
-use crate::config::Config;
-use crate::projects::ProjectStore;
-use crate::tui::{filter_projects, select_project, SelectionResult};
+use crate::commands::{group, profile};
+use crate::commands::tag::edit_tags;
+use crate::config::{Config, SessionWindow};
+use crate::directive;
+use crate::launch::CommandTemplate;
+use crate::multiplexer;
+use crate::projects::{Group, Project, ProjectStore};
+use crate::tui::{filter_projects, fuzzy_match, select_project, PickerAction, SelectionResult};
 
-fn shell_escape(path: &std::path::Path) -> String {
+pub fn shell_escape(path: &Path) -> String {
     let s = path.display().to_string();
     format!("'{}'", s.replace('\'', "'\\''"))
 }
 
-pub fn run(
-    filters: Vec<String>,
-    editor_override: Option<String>,
-    cd_override: Option<bool>,
-    ai_override: Option<String>,
-) -> Result<()> {
+/// Per-invocation overrides of the editor, cd and AI assistant settings.
+/// An empty editor or AI assistant string means "don't open".
+#[derive(Default)]
+pub struct LaunchOverrides {
+    pub editor: Option<String>,
+    pub cd: Option<bool>,
+    pub ai: Option<String>,
+    /// Launch profile run instead of the editor and AI assistant
+    pub profile: Option<String>,
+    /// Open the project in its own tmux session
+    pub session: Option<bool>,
+    /// Start the AI assistant in its own multiplexer pane
+    pub ai_pane: Option<bool>,
+}
+
+impl LaunchOverrides {
+    /// Editor to launch, or `None` when disabled with --no-editor or set to
+    /// `none`
+    pub fn editor(&self, config: &Config) -> Result<Option<CommandTemplate>> {
+        let editor = match &self.editor {
+            Some(e) if e.is_empty() => return Ok(None),
+            Some(e) => e,
+            None => &config.editor,
+        };
+        if editor == "none" {
+            return Ok(None);
+        }
+        CommandTemplate::parse(editor).map(Some)
+    }
+
+    pub fn cd(&self, config: &Config) -> bool {
+        self.cd.unwrap_or(config.cd_on_select)
+    }
+
+    pub fn session(&self, config: &Config) -> bool {
+        self.session.unwrap_or(config.session.enabled)
+    }
+
+    /// Whether the AI assistant gets its own multiplexer pane: the flag, then
+    /// the project's setting, then config
+    pub fn ai_pane(&self, config: &Config, project: Option<&Project>) -> bool {
+        self.ai_pane
+            .or(project.and_then(|p| p.ai_pane))
+            .unwrap_or(config.ai_pane)
+    }
+
+    /// AI assistant to start, or `None` when disabled with --no-ai or set to
+    /// `none`
+    pub fn ai(&self, config: &Config) -> Result<Option<CommandTemplate>> {
+        let ai = match &self.ai {
+            Some(a) if a.is_empty() => return Ok(None),
+            Some(a) => a,
+            None => &config.ai_assistant,
+        };
+        if ai == "none" {
+            return Ok(None);
+        }
+        CommandTemplate::parse(ai).map(Some)
+    }
+}
+
+pub fn run(filters: Vec<String>, overrides: LaunchOverrides) -> Result<()> {
+    directive::check_shell()?;
     let config = Config::load()?;
     let mut store = ProjectStore::load()?;
 
+    if store.sorted_by_frecency().is_empty() {
+        bail!("No projects found. Add a project with: pj -a");
+    }
+
+    // Picker actions such as pinning or editing tags return to the picker;
+    // only the first pass may auto-open a single match
+    let mut auto_select = true;
+    let (selected_paths, action) = loop {
+        let result = pick(&store, &filters, &config, auto_select)?;
+        auto_select = false;
+
+        match result {
+            SelectionResult::Selected(path) => break (vec![path], None),
+            SelectionResult::MultiSelected(paths) => break (paths, None),
+            SelectionResult::Group(name) => {
+                return group::open(&config, &mut store, &name, overrides);
+            }
+            SelectionResult::MissingSelected(path) => {
+                handle_missing_project(&mut store, &path)?;
+                std::process::exit(1);
+            }
+            SelectionResult::Action(
+                action @ (PickerAction::EditorOnly
+                | PickerAction::CdOnly
+                | PickerAction::AiOnly
+                | PickerAction::RunProfile),
+                path,
+            ) => break (vec![path], Some(action)),
+            SelectionResult::Action(action, path) => {
+                run_picker_action(action, &mut store, &path)?;
+                if store.sorted_by_frecency().is_empty() {
+                    return Ok(());
+                }
+            }
+            SelectionResult::Cancelled => {
+                std::process::exit(130);
+            }
+        }
+    };
+
+    let overrides = match action {
+        Some(PickerAction::EditorOnly) => LaunchOverrides {
+            editor: Some(action_command(&overrides.editor, &config.editor, "editor")?),
+            cd: Some(false),
+            ai: Some(String::new()),
+            profile: None,
+            session: Some(false),
+            ..overrides
+        },
+        Some(PickerAction::CdOnly) => LaunchOverrides {
+            editor: Some(String::new()),
+            cd: Some(true),
+            ai: Some(String::new()),
+            profile: None,
+            session: Some(false),
+            ..overrides
+        },
+        Some(PickerAction::AiOnly) => LaunchOverrides {
+            editor: Some(String::new()),
+            cd: Some(false),
+            ai: Some(action_command(&overrides.ai, &config.ai_assistant, "ai_assistant")?),
+            profile: None,
+            session: Some(false),
+            ..overrides
+        },
+        Some(PickerAction::RunProfile) => LaunchOverrides {
+            profile: Some(profile::choose(&config, None)?),
+            ..overrides
+        },
+        _ => overrides,
+    };
+
+    open_projects(&config, &mut store, &selected_paths, overrides)
+}
+
+/// Command run by the editor-only and AI-only picker actions: the
+/// command-line override, or the configured command even when --no-editor or
+/// --no-ai disabled it
+fn action_command(overridden: &Option<String>, configured: &str, key: &str) -> Result<String> {
+    let command = overridden
+        .as_deref()
+        .filter(|command| !command.is_empty())
+        .unwrap_or(configured);
+    if command == "none" {
+        bail!("Nothing to open: {} = \"none\"", key);
+    }
+    Ok(command.to_string())
+}
+
+/// Apply filters and pick a project: auto-select a single match when allowed,
+/// otherwise show the picker
+fn pick(
+    store: &ProjectStore,
+    filters: &[String],
+    config: &Config,
+    auto_select: bool,
+) -> Result<SelectionResult> {
     let all_projects = store.sorted_by_frecency();
+    let all_groups: Vec<&Group> = store.groups().iter().collect();
 
-    if all_projects.is_empty() {
-        bail!("No projects found. Add a project with: pj -a");
+    if filters.is_empty() {
+        // No filter: show all projects in skim
+        return select_project(&all_projects, &all_groups, None, config);
     }
 
     // Apply filter logic
-    let filtered_projects = filter_projects(&all_projects, &filters);
+    let filtered_projects = filter_projects(&all_projects, filters);
+    let filtered_groups: Vec<&Group> = all_groups
+        .iter()
+        .filter(|g| {
+            let searchable = g.searchable_text();
+            filters.iter().all(|filter| fuzzy_match(&searchable, filter))
+        })
+        .copied()
+        .collect();
 
     // Filter out missing projects for auto-selection consideration
     let existing_filtered: Vec<_> = filtered_projects
@@ -36,99 +207,288 @@ pub fn run(
         .copied()
         .collect();
 
-    let selected_path = if filters.is_empty() {
-        // No filter: show all projects in skim
-        match select_project(&all_projects, None)? {
-            SelectionResult::Selected(path) => path,
-            SelectionResult::MissingSelected(path) => {
-                handle_missing_project(&mut store, &path)?;
-                std::process::exit(1);
-            }
-            SelectionResult::Cancelled | SelectionResult::MultiSelected(_) => {
-                std::process::exit(130);
-            }
+    if auto_select {
+        if let Some(project) = single_match(&existing_filtered) {
+            // Single match: auto-open silently
+            return Ok(SelectionResult::Selected(project.path.clone()));
         }
-    } else if existing_filtered.len() == 1 {
-        // Single match: auto-open silently
-        existing_filtered[0].path.clone()
+        if existing_filtered.is_empty() && filtered_groups.len() == 1 {
+            return Ok(SelectionResult::Group(filtered_groups[0].name.clone()));
+        }
+    }
+
+    // Zero or multiple matches: show skim with filter pre-populated
+    let query = filters.join(" ");
+    if filtered_projects.is_empty() && filtered_groups.is_empty() {
+        // Zero matches: show everything so user can modify query
+        select_project(&all_projects, &all_groups, Some(&query), config)
     } else {
-        // Zero or multiple matches: show skim with filter pre-populated
-        let query = filters.join(" ");
-        let projects_to_show = if filtered_projects.is_empty() {
-            // Zero matches: show all projects so user can modify query
-            &all_projects
-        } else {
-            // Multiple matches: show filtered projects
-            &filtered_projects
-        };
+        // Multiple matches: show filtered projects and groups
+        select_project(&filtered_projects, &filtered_groups, Some(&query), config)
+    }
+}
 
-        match select_project(projects_to_show, Some(&query))? {
-            SelectionResult::Selected(path) => path,
-            SelectionResult::MissingSelected(path) => {
-                handle_missing_project(&mut store, &path)?;
-                std::process::exit(1);
+/// Run a picker action that does not open the project
+fn run_picker_action(action: PickerAction, store: &mut ProjectStore, path: &Path) -> Result<()> {
+    match action {
+        PickerAction::EditTags => edit_tags(store, path)?,
+        PickerAction::TogglePin => {
+            if let Some(project) = store.find_by_path_mut(path) {
+                project.pinned = !project.pinned;
+                let state = if project.pinned { "Pinned" } else { "Unpinned" };
+                eprintln!("{}: {}", state, path.display());
+                store.save()?;
             }
-            SelectionResult::Cancelled | SelectionResult::MultiSelected(_) => {
-                std::process::exit(130);
+        }
+        PickerAction::Remove => {
+            let remove = Confirm::new()
+                .with_prompt(format!("Remove {} from tracking?", path.display()))
+                .default(false)
+                .interact()?;
+            if remove && store.remove(path) {
+                store.save()?;
+                eprintln!("Removed: {}", path.display());
             }
         }
+        PickerAction::FileManager => open_in_file_manager(path)?,
+        PickerAction::CopyPath => copy_to_clipboard(&path.display().to_string())?,
+        PickerAction::EditorOnly
+        | PickerAction::CdOnly
+        | PickerAction::AiOnly
+        | PickerAction::RunProfile => {}
+    }
+    Ok(())
+}
+
+fn open_in_file_manager(path: &Path) -> Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
     };
+    directive::isolate(&mut Command::new(opener))
+        .arg(path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to launch file manager '{}': {}", opener, e))?;
+    Ok(())
+}
+
+/// Clipboard commands tried in order
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[
+    &["pbcopy"],
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+    &["clip.exe"],
+];
 
-    // Update frecency for selected project
-    if let Some(project) = store.find_by_path_mut(&selected_path) {
-        project.update_access();
+fn copy_to_clipboard(text: &str) -> Result<()> {
+    for command in CLIPBOARD_COMMANDS {
+        let child = directive::isolate(&mut Command::new(command[0]))
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let Ok(mut child) = child else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            eprintln!("Copied: {}", text);
+            return Ok(());
+        }
     }
-    store.save()?;
+    bail!("No clipboard command found (tried pbcopy, wl-copy, xclip, xsel)")
+}
+
+/// Open a tracked project: update its frecency, launch the editor, and print
+/// the cd target and AI assistant command for the shell function
+pub fn open_project(
+    config: &Config,
+    store: &mut ProjectStore,
+    selected_path: &Path,
+    overrides: LaunchOverrides,
+) -> Result<()> {
+    open_projects(config, store, &[selected_path.to_path_buf()], overrides)
+}
 
-    // Handle editor
-    let should_open_editor = match &editor_override {
-        Some(e) if e.is_empty() => false, // --no-editor was used
-        _ => true,
+/// Open several tracked projects: update frecency and open the editor for
+/// each, cd into the first one, and start the AI assistant for the first one
+/// in the foreground (or its own pane) and in new multiplexer windows for the
+/// others
+pub fn open_projects(
+    config: &Config,
+    store: &mut ProjectStore,
+    selected_paths: &[PathBuf],
+    overrides: LaunchOverrides,
+) -> Result<()> {
+    let Some(first_path) = selected_paths.first() else {
+        return Ok(());
     };
 
-    if should_open_editor {
-        let editor = editor_override
-            .filter(|e| !e.is_empty())
-            .unwrap_or(config.editor);
+    // Update frecency for the selected projects
+    for path in selected_paths {
+        if let Some(project) = store.find_by_path_mut(path) {
+            project.update_access();
+        }
+    }
+    store.save()?;
 
-        if !crate::multiplexer::try_open_in_split(&editor, &selected_path) {
-            Command::new(&editor)
-                .arg(&selected_path)
-                .spawn()
-                .map_err(|e| anyhow::anyhow!("Failed to launch editor '{}': {}", editor, e))?;
+    if overrides.profile.is_none() && overrides.session(config) {
+        return open_sessions(config, selected_paths, &overrides);
+    }
+
+    // The shell can only be in one directory: the first selected project's.
+    // It changes directory before running the editor and AI assistant.
+    if overrides.cd(config) {
+        directive::cd(first_path)?;
+    }
+
+    if let Some(name) = &overrides.profile {
+        let name = profile::choose(config, Some(name))?;
+        profile::run(config, store, &name, selected_paths)?;
+    } else if let Some(editor) = overrides.editor(config)? {
+        let terminal = editor.is_terminal(&config.terminal_editors);
+        for (index, path) in selected_paths.iter().enumerate() {
+            let line = editor.editor_line(path);
+            if terminal && multiplexer::try_open_in_split(config, &line, path) {
+                continue;
+            }
+            if !terminal {
+                editor.spawn_editor(path)?;
+            } else if index == 0 {
+                run_in_shell(&line)?;
+            } else {
+                // Printed by the shell once the editor exits
+                directive::echo(&format!(
+                    "Not in a multiplexer: opened {} for {} only",
+                    editor.program(),
+                    first_path.display()
+                ))?;
+                break;
+            }
         }
     }
 
-    // Handle cd output
-    let should_cd = cd_override.unwrap_or(config.cd_on_select);
-    if should_cd {
-        println!("{}", selected_path.display());
+    if let Some(ai) = overrides.ai(config)?.filter(|_| overrides.profile.is_none()) {
+        for path in &selected_paths[1..] {
+            if !multiplexer::try_open_in_window(config, &ai.ai_line(path), path) {
+                eprintln!(
+                    "Not in a multiplexer: starting {} for {} only",
+                    ai.program(),
+                    first_path.display()
+                );
+                break;
+            }
+        }
+        open_ai(
+            config,
+            first_path,
+            &ai,
+            overrides.ai_pane(config, store.find_by_path(first_path)),
+        )?;
     }
 
-    // Handle AI assistant output
-    let should_open_ai = match &ai_override {
-        Some(a) if a.is_empty() => false,
-        _ => true,
-    };
+    Ok(())
+}
+
+/// Start the AI assistant in its own multiplexer pane when asked to and
+/// possible, otherwise hand it to the shell function
+pub fn open_ai(config: &Config, path: &Path, ai: &CommandTemplate, pane: bool) -> Result<()> {
+    if pane && multiplexer::try_open_in_split(config, &ai.ai_line(path), path) {
+        return Ok(());
+    }
+    start_ai_in_shell(path, ai)
+}
 
-    if should_open_ai {
-        let ai = ai_override
-            .filter(|a| !a.is_empty())
-            .unwrap_or(config.ai_assistant);
-
-        if ai != "none" {
-            println!(
-                "__PJ_AI__:cd {} && {}",
-                shell_escape(&selected_path),
-                ai
-            );
+/// Session-per-project mode: make sure each project has a tmux session with
+/// the configured windows, then switch to the first project's session
+fn open_sessions(
+    config: &Config,
+    selected_paths: &[PathBuf],
+    overrides: &LaunchOverrides,
+) -> Result<()> {
+    let editor = overrides.editor(config)?;
+    let ai = overrides.ai(config)?;
+
+    let mut first_session = None;
+    for path in selected_paths {
+        let mut windows = Vec::new();
+        for window in &config.session.windows {
+            match window {
+                SessionWindow::Editor => match &editor {
+                    Some(editor) if editor.is_terminal(&config.terminal_editors) => {
+                        windows.push(("editor", Some(editor.editor_line(path))));
+                    }
+                    Some(editor) => editor.spawn_editor(path)?,
+                    None => {}
+                },
+                SessionWindow::Shell => windows.push(("shell", None)),
+                SessionWindow::Ai => {
+                    if let Some(ai) = &ai {
+                        windows.push(("ai", Some(ai.ai_line(path))));
+                    }
+                }
+            }
         }
+
+        let name = multiplexer::ensure_tmux_session(path, &windows)
+            .map_err(|e| anyhow::anyhow!("Failed to create tmux session: {}", e))?;
+        first_session.get_or_insert(name);
+    }
+
+    // The shell can only be in one directory: the first selected project's
+    if overrides.cd(config) {
+        directive::cd(&selected_paths[0])?;
     }
 
+    if let Some(name) = first_session {
+        let attach = multiplexer::switch_to_tmux_session(&name)
+            .map_err(|e| anyhow::anyhow!("Failed to switch to tmux session '{}': {}", name, e))?;
+        if let Some(command) = attach {
+            run_in_shell(&command)?;
+        }
+    }
     Ok(())
 }
 
-fn handle_missing_project(store: &mut ProjectStore, path: &std::path::Path) -> Result<()> {
+/// Hand a terminal program to the shell function, which runs it in the
+/// foreground
+pub fn run_in_shell(command: &str) -> Result<()> {
+    directive::eval(command)
+}
+
+/// Hand the AI assistant command to the shell function, which runs it in the
+/// foreground inside the project
+pub fn start_ai_in_shell(path: &Path, ai: &CommandTemplate) -> Result<()> {
+    directive::eval(&format!("cd {} && {}", shell_escape(path), ai.ai_line(path)))
+}
+
+/// The only match, counting a project together with its own worktrees and
+/// workspace members as one match for the project itself
+pub fn single_match<'a>(matches: &[&'a Project]) -> Option<&'a Project> {
+    if matches.len() == 1 {
+        return Some(matches[0]);
+    }
+    let mut top_level = matches.iter().filter(|p| p.parent.is_none());
+    let project = top_level.next()?;
+    if top_level.next().is_some() {
+        return None;
+    }
+    matches
+        .iter()
+        .all(|p| p.parent.is_none() || p.parent.as_deref() == Some(project.path.as_path()))
+        .then_some(*project)
+}
+
+fn handle_missing_project(store: &mut ProjectStore, path: &Path) -> Result<()> {
     eprintln!("Project path does not exist: {}", path.display());
     let remove = Confirm::new()
         .with_prompt("Remove from tracking?")
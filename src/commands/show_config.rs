@@ -193,6 +193,7 @@ impl ConfigEditor {
             git_init_on_add: self.config.git_init_on_add,
             gh_create_on_add: self.config.gh_create_on_add,
             ai_assistant,
+            ..self.config.clone()
         }
     }
 }
@@ -240,6 +241,7 @@ pub fn run() -> Result<()> {
 
     match result {
         Ok(Some(new_config)) => {
+            new_config.validate_commands()?;
             new_config.save()?;
             eprintln!("Configuration saved.");
         }
@@ -1,22 +1,53 @@
 use anyhow::{bail, Result};
 use dialoguer::{Completion, Input};
+use std::collections::BTreeMap;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tabled::{Table, Tabled};
 
-use crate::projects::ProjectStore;
+use crate::config::Config;
+use crate::projects::{normalize_tag, ProjectStore};
 use crate::tui::{select_projects_multi, SelectionResult};
 
 struct TagCompletion {
     tags: Vec<String>,
+    values_by_key: BTreeMap<String, Vec<String>>,
+}
+
+impl TagCompletion {
+    fn from_store(store: &ProjectStore) -> Self {
+        Self {
+            tags: store.all_tags(),
+            values_by_key: store.tag_values_by_key(),
+        }
+    }
 }
 
 impl Completion for TagCompletion {
+    /// Complete the last comma-separated tag; after `key=`, complete from the
+    /// values already used with that key
     fn get(&self, input: &str) -> Option<String> {
-        let input_lower = input.to_lowercase();
-        self.tags
-            .iter()
-            .find(|tag| tag.starts_with(&input_lower))
-            .cloned()
+        let (head, current) = match input.rfind(',') {
+            Some(idx) => input.split_at(idx + 1),
+            None => ("", input),
+        };
+        let current_lower = current.trim_start().to_lowercase();
+
+        let completed = match current_lower.split_once('=') {
+            Some((key, value)) => self
+                .values_by_key
+                .get(key.trim())
+                .and_then(|values| values.iter().find(|v| v.starts_with(value.trim())))
+                .map(|v| format!("{}={}", key.trim(), v)),
+            None => self
+                .tags
+                .iter()
+                .find(|tag| tag.starts_with(&current_lower))
+                .cloned(),
+        }?;
+
+        let separator = if head.is_empty() { "" } else { " " };
+        Some(format!("{}{}{}", head, separator, completed))
     }
 }
 
@@ -24,14 +55,14 @@ impl Completion for TagCompletion {
 fn parse_tags(tags_str: &str) -> Vec<String> {
     tags_str
         .split(',')
-        .map(|s| s.trim().to_lowercase())
+        .map(normalize_tag)
         .filter(|s| !s.is_empty())
         .collect()
 }
 
 /// Prompt user for tags with completion
-fn prompt_for_tags(all_tags: Vec<String>) -> Result<Vec<String>> {
-    let completion = TagCompletion { tags: all_tags };
+fn prompt_for_tags(store: &ProjectStore) -> Result<Vec<String>> {
+    let completion = TagCompletion::from_store(store);
 
     let input: String = Input::new()
         .with_prompt("Enter tags (comma-separated)")
@@ -45,6 +76,26 @@ fn prompt_for_tags(all_tags: Vec<String>) -> Result<Vec<String>> {
     Ok(tags)
 }
 
+/// Prompt for a project's manual tags, prefilled with the current ones, and
+/// replace them
+pub fn edit_tags(store: &mut ProjectStore, path: &Path) -> Result<()> {
+    let completion = TagCompletion::from_store(store);
+    let project = store
+        .find_by_path_mut(path)
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", path.display()))?;
+
+    let input: String = Input::new()
+        .with_prompt(format!("Tags for {}", path.display()))
+        .with_initial_text(project.tags.join(", "))
+        .allow_empty(true)
+        .completion_with(&completion)
+        .interact_text()?;
+
+    project.tags = parse_tags(&input);
+    eprintln!("Current tags: {}", project.tags.join(", "));
+    store.save()
+}
+
 pub fn run(tags: Option<String>, path: Option<PathBuf>, remove: bool) -> Result<()> {
     let mut store = ProjectStore::load()?;
 
@@ -67,14 +118,14 @@ pub fn run(tags: Option<String>, path: Option<PathBuf>, remove: bool) -> Result<
             bail!("No projects tracked. Add a project with: pj -a");
         }
 
-        match select_projects_multi(&projects)? {
+        match select_projects_multi(&projects, &Config::load()?)? {
             SelectionResult::MultiSelected(paths) => paths,
-            SelectionResult::Selected(path) => vec![path],
+            SelectionResult::Selected(path) | SelectionResult::Action(_, path) => vec![path],
             SelectionResult::MissingSelected(path) => {
                 eprintln!("Project path does not exist: {}", path.display());
                 bail!("Cannot manage tags for missing project");
             }
-            SelectionResult::Cancelled => {
+            SelectionResult::Cancelled | SelectionResult::Group(_) => {
                 std::process::exit(130);
             }
         }
@@ -87,8 +138,7 @@ pub fn run(tags: Option<String>, path: Option<PathBuf>, remove: bool) -> Result<
     // Determine tags to apply
     let final_tags = if tags_vec.is_empty() {
         // No tags provided: prompt for them
-        let all_tags = store.all_tags();
-        prompt_for_tags(all_tags)?
+        prompt_for_tags(&store)?
     } else {
         tags_vec
     };
@@ -120,3 +170,150 @@ pub fn run(tags: Option<String>, path: Option<PathBuf>, remove: bool) -> Result<
     store.save()?;
     Ok(())
 }
+
+#[derive(Tabled)]
+struct TagRow {
+    #[tabled(rename = "TAG")]
+    tag: String,
+    #[tabled(rename = "PROJECTS")]
+    count: usize,
+}
+
+/// List every tag in the store with the number of projects using it
+pub fn list_all() -> Result<()> {
+    let store = ProjectStore::load()?;
+    let counts = store.tag_counts();
+
+    if counts.is_empty() {
+        eprintln!("No tags found");
+        return Ok(());
+    }
+
+    let rows: Vec<TagRow> = counts
+        .into_iter()
+        .map(|(tag, count)| TagRow { tag, count })
+        .collect();
+
+    println!("{}", Table::new(rows));
+    Ok(())
+}
+
+/// Rename a tag on every project that carries it
+pub fn rename(old: &str, new: &str, dry_run: bool) -> Result<()> {
+    let old_tags = parse_tags(old);
+    if old_tags.len() != 1 {
+        bail!("Tag to rename must be a single tag (use --merge-tags to combine several)");
+    }
+    let new_tags = parse_tags(new);
+    if new_tags.len() != 1 {
+        bail!("New tag name must be a single tag");
+    }
+    replace_everywhere(old_tags, Some(&new_tags[0]), dry_run)
+}
+
+/// Merge several tags into a single target tag on every project
+pub fn merge(sources: &str, target: &str, dry_run: bool) -> Result<()> {
+    let target_tags = parse_tags(target);
+    if target_tags.len() != 1 {
+        bail!("Merge target must be a single tag");
+    }
+    replace_everywhere(parse_tags(sources), Some(&target_tags[0]), dry_run)
+}
+
+/// Delete tags from every project that carries them
+pub fn delete(tags: &str, dry_run: bool) -> Result<()> {
+    replace_everywhere(parse_tags(tags), None, dry_run)
+}
+
+fn replace_everywhere(from: Vec<String>, to: Option<&str>, dry_run: bool) -> Result<()> {
+    if from.is_empty() {
+        bail!("No tags given");
+    }
+
+    let mut store = ProjectStore::load()?;
+
+    let known_tags = store.all_tags();
+    for tag in from.iter().filter(|t| !known_tags.contains(t)) {
+        eprintln!("Warning: tag '{}' is not used by any project", tag);
+    }
+
+    let affected: Vec<PathBuf> = store
+        .projects_with_any_tag(&from)
+        .iter()
+        .map(|p| p.path.clone())
+        .collect();
+
+    if affected.is_empty() {
+        eprintln!("No projects to update");
+        return Ok(());
+    }
+
+    let from_str = from.join(", ");
+    let (verb, description) = match to {
+        Some(to) => ("replace", format!("{} with {} in", from_str, to)),
+        None => ("delete", format!("{} from", from_str)),
+    };
+
+    if dry_run {
+        eprintln!("Would {} {} {} projects:", verb, description, affected.len());
+    } else {
+        let past = if to.is_some() { "Replaced" } else { "Deleted" };
+        eprintln!("{} {} {} projects:", past, description, affected.len());
+    }
+    for path in &affected {
+        eprintln!("  {}", path.display());
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    store.replace_tags(&from, to);
+    store.save()?;
+    Ok(())
+}
+
+/// Re-detect auto tags for every tracked project
+pub fn refresh_auto() -> Result<()> {
+    let mut store = ProjectStore::load()?;
+    let changed = store.refresh_auto_tags();
+    eprintln!("Refreshed auto tags ({} projects changed)", changed);
+    if changed > 0 {
+        store.save()?;
+    }
+    Ok(())
+}
+
+/// Add the tags from config tag rules to every matching project
+pub fn apply_rules(dry_run: bool) -> Result<()> {
+    let config = Config::load()?;
+    if config.tag_rules.is_empty() {
+        eprintln!("No tag rules configured in {}", Config::config_path()?.display());
+        return Ok(());
+    }
+
+    let mut store = ProjectStore::load()?;
+    let updates = store.missing_rule_tags(&config);
+
+    if updates.is_empty() {
+        eprintln!("All projects already match the tag rules");
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would add" } else { "Added" };
+    for (path, tags) in &updates {
+        eprintln!("{} {} to {}", verb, tags.join(", "), path.display());
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    for (path, tags) in updates {
+        if let Some(project) = store.find_by_path_mut(&path) {
+            project.add_tags(tags);
+        }
+    }
+    store.save()?;
+    Ok(())
+}
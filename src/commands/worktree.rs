@@ -0,0 +1,106 @@
+use anyhow::{bail, Context, Result};
+use std::env;
+
+use crate::config::Config;
+use crate::directive;
+use crate::git::{add_worktree, is_git_repo, remove_worktree};
+use crate::projects::ProjectStore;
+
+/// Discover the worktrees of every tracked git project
+pub fn sync() -> Result<()> {
+    let config = Config::load()?;
+    let mut store = ProjectStore::load()?;
+
+    let repos: Vec<_> = store
+        .sorted_by_frecency()
+        .into_iter()
+        .filter(|p| p.parent.is_none() && p.exists() && is_git_repo(&p.path))
+        .map(|p| p.path.clone())
+        .collect();
+
+    let (mut added, mut removed) = (0, 0);
+    for repo in &repos {
+        match store.sync_worktrees(&config, repo) {
+            Ok((a, r)) => {
+                added += a;
+                removed += r;
+            }
+            Err(e) => eprintln!("Warning: {}: {}", repo.display(), e),
+        }
+    }
+
+    eprintln!("Worktrees: {} added, {} removed", added, removed);
+    if added > 0 || removed > 0 {
+        store.save()?;
+    }
+    Ok(())
+}
+
+/// Create a worktree for `branch` next to the tracked project containing the
+/// current directory, and track it
+pub fn add(branch: &str) -> Result<()> {
+    directive::check_shell()?;
+    let config = Config::load()?;
+    let mut store = ProjectStore::load()?;
+
+    let cwd = env::current_dir().context("Failed to get current directory")?;
+    let cwd = cwd.canonicalize().unwrap_or(cwd);
+    let repo = store
+        .find_containing(&cwd)
+        .map(|p| p.path.clone())
+        .context("Current directory is not inside a tracked project")?;
+
+    if !is_git_repo(&repo) {
+        bail!("Not a git repository: {}", repo.display());
+    }
+
+    let name = repo
+        .file_name()
+        .and_then(|n| n.to_str())
+        .context("Invalid project directory name")?;
+    let worktree_path = repo
+        .with_file_name(format!("{}-{}", name, branch.replace('/', "-")));
+    if worktree_path.exists() {
+        bail!("Path already exists: {}", worktree_path.display());
+    }
+
+    add_worktree(&repo, &worktree_path, branch)?;
+    store.sync_worktrees(&config, &repo)?;
+    store.save()?;
+
+    eprintln!("Created worktree: {} ({})", worktree_path.display(), branch);
+
+    if config.cd_on_select {
+        let worktree_path = worktree_path.canonicalize().unwrap_or(worktree_path);
+        directive::cd(&worktree_path)?;
+    }
+    Ok(())
+}
+
+/// Remove the worktree checked out at `branch` for the tracked project
+/// containing the current directory
+pub fn remove(branch: &str) -> Result<()> {
+    let config = Config::load()?;
+    let mut store = ProjectStore::load()?;
+
+    let cwd = env::current_dir().context("Failed to get current directory")?;
+    let cwd = cwd.canonicalize().unwrap_or(cwd);
+    let repo = store
+        .find_containing(&cwd)
+        .map(|p| p.path.clone())
+        .context("Current directory is not inside a tracked project")?;
+
+    let worktree_path = store
+        .worktrees_of(&repo)
+        .into_iter()
+        .find(|w| w.branch.as_deref() == Some(branch))
+        .map(|w| w.path.clone())
+        .with_context(|| format!("No worktree for branch '{}' in {}", branch, repo.display()))?;
+
+    remove_worktree(&repo, &worktree_path)?;
+    store.sync_worktrees(&config, &repo)?;
+    store.save()?;
+
+    eprintln!("Removed worktree: {}", worktree_path.display());
+    Ok(())
+}
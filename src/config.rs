@@ -1,7 +1,13 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::glob::{expand_tilde, matches_path};
+use crate::launch;
+use crate::picker::PickerKind;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
@@ -12,12 +18,220 @@ pub struct Config {
     pub gh_create_on_add: bool,
     #[serde(default = "default_ai_assistant")]
     pub ai_assistant: String,
+    /// Inside a multiplexer, start the AI assistant in its own pane instead
+    /// of the current shell
+    #[serde(default)]
+    pub ai_pane: bool,
+    #[serde(default = "default_clone_root")]
+    pub clone_root: String,
+    #[serde(default = "default_clone_layout")]
+    pub clone_layout: String,
+    /// Show the project preview pane in the picker
+    #[serde(default = "default_true")]
+    pub preview: bool,
+    /// Preview pane layout, e.g. `right:50%`, `down:40%` or `right:50%:hidden`
+    #[serde(default = "default_preview_window")]
+    pub preview_window: String,
+    /// Key that shows or hides the preview pane
+    #[serde(default = "default_preview_toggle_key")]
+    pub preview_toggle_key: String,
+    /// Editors that run in the terminal: opened in a multiplexer split or in
+    /// the foreground instead of being spawned in the background
+    #[serde(default = "default_terminal_editors")]
+    pub terminal_editors: Vec<String>,
+    /// Where terminal editors open inside a multiplexer
+    #[serde(default)]
+    pub split: SplitLayout,
+    /// tmux session-per-project mode
+    #[serde(default)]
+    pub session: SessionLayout,
+    /// Picker backend: auto, skim, fzf or plain
+    #[serde(default)]
+    pub picker: PickerKind,
+    /// Show git branch, dirty state, ahead/behind and last commit columns in
+    /// the picker and `--list`
+    #[serde(default)]
+    pub git_status: bool,
+    /// How long to wait for git status before showing projects without it
+    #[serde(default = "default_git_status_timeout_ms")]
+    pub git_status_timeout_ms: u64,
+    /// Directory `pj --new` creates projects in (current directory if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_root: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag_rules: Vec<TagRule>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, Template>,
+    /// Named commands run in a project, with `{path}`, `{name}` and `{tags}`
+    /// placeholders
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, String>,
+    #[serde(default)]
+    pub keys: KeyBindings,
+}
+
+/// Picker keys acting on the highlighted project (skim key names such as
+/// `alt-e` or `ctrl-o`; an empty string disables the action)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub editor: String,
+    pub cd: String,
+    pub ai: String,
+    pub tags: String,
+    pub pin: String,
+    pub remove: String,
+    pub file_manager: String,
+    pub copy_path: String,
+    pub profile: String,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            editor: "alt-e".to_string(),
+            cd: "alt-c".to_string(),
+            ai: "alt-a".to_string(),
+            tags: "alt-t".to_string(),
+            pin: "alt-s".to_string(),
+            remove: "alt-d".to_string(),
+            file_manager: "alt-o".to_string(),
+            copy_path: "alt-y".to_string(),
+            profile: "alt-r".to_string(),
+        }
+    }
+}
+
+/// Multiplexer pane for terminal editors. cmux supports `direction` only,
+/// Zellij `direction` and `window`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SplitLayout {
+    pub direction: SplitDirection,
+    /// Pane size in percent of the current pane
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u8>,
+    /// Move focus to the new pane
+    pub focus: bool,
+    /// Open a tmux window named after the project instead of a split
+    pub window: bool,
+    /// How long to wait for a new cmux pane's shell before typing into it
+    pub cmux_ready_timeout_ms: u64,
+}
+
+impl Default for SplitLayout {
+    fn default() -> Self {
+        Self {
+            direction: SplitDirection::default(),
+            size: None,
+            focus: false,
+            window: false,
+            cmux_ready_timeout_ms: default_cmux_ready_timeout_ms(),
+        }
+    }
+}
+
+impl SplitLayout {
+    pub fn cmux_ready_timeout(&self) -> Duration {
+        Duration::from_millis(self.cmux_ready_timeout_ms)
+    }
+}
+
+/// `horizontal` puts the panes side by side, `vertical` stacks them
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+/// One tmux session per project, named after the project directory
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SessionLayout {
+    /// Switch to (or create) the project's session when opening a project
+    pub enabled: bool,
+    /// Windows created in a new session, in order; a single shell when empty
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<SessionWindow>,
+}
+
+/// A window in a new project session
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionWindow {
+    Editor,
+    Shell,
+    Ai,
+}
+
+/// Tags applied to every project whose path matches a glob pattern
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagRule {
+    pub path: String,
+    pub tags: Vec<String>,
+}
+
+/// Project template used by `pj --new`: a local directory or git repository
+/// whose files may contain `{{name}}`-style variables
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Template {
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_create: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, String>,
+}
+
+impl TagRule {
+    pub fn matches(&self, path: &Path) -> bool {
+        matches_path(&expand_tilde(&self.path), path)
+    }
 }
 
 fn default_ai_assistant() -> String {
     "none".to_string()
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_preview_window() -> String {
+    "right:50%".to_string()
+}
+
+fn default_preview_toggle_key() -> String {
+    "alt-p".to_string()
+}
+
+fn default_terminal_editors() -> Vec<String> {
+    [
+        "vi", "vim", "nvim", "hx", "helix", "kak", "micro", "nano", "pico", "emacs -nw",
+        "emacsclient -nw", "joe", "ne", "mg",
+    ]
+    .iter()
+    .map(|e| e.to_string())
+    .collect()
+}
+
+fn default_git_status_timeout_ms() -> u64 {
+    500
+}
+
+fn default_cmux_ready_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_clone_root() -> String {
+    "~/src".to_string()
+}
+
+fn default_clone_layout() -> String {
+    "{host}/{owner}/{repo}".to_string()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -26,6 +240,23 @@ impl Default for Config {
             git_init_on_add: true,
             gh_create_on_add: false,
             ai_assistant: "none".to_string(),
+            ai_pane: false,
+            clone_root: default_clone_root(),
+            clone_layout: default_clone_layout(),
+            preview: true,
+            preview_window: default_preview_window(),
+            preview_toggle_key: default_preview_toggle_key(),
+            terminal_editors: default_terminal_editors(),
+            split: SplitLayout::default(),
+            session: SessionLayout::default(),
+            picker: PickerKind::default(),
+            git_status: false,
+            git_status_timeout_ms: default_git_status_timeout_ms(),
+            new_root: None,
+            tag_rules: Vec::new(),
+            templates: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            keys: KeyBindings::default(),
         }
     }
 }
@@ -44,6 +275,15 @@ impl Config {
         Ok(Self::pj_dir()?.join("projects.json"))
     }
 
+    pub fn groups_path() -> Result<PathBuf> {
+        Ok(Self::pj_dir()?.join("groups.json"))
+    }
+
+    /// Where generated VS Code workspaces for groups are written
+    pub fn workspaces_dir() -> Result<PathBuf> {
+        Ok(Self::pj_dir()?.join("workspaces"))
+    }
+
     pub fn load() -> Result<Self> {
         let path = Self::config_path()?;
         if !path.exists() {
@@ -57,7 +297,19 @@ impl Config {
         Ok(config)
     }
 
+    /// Check that the editor and AI assistant commands parse
+    pub fn validate_commands(&self) -> Result<()> {
+        launch::validate("editor", &self.editor)?;
+        launch::validate("AI assistant", &self.ai_assistant)
+    }
+
     fn validate_and_warn(&self) {
+        if let Err(e) = self.validate_commands() {
+            eprintln!("Warning: {:#}", e);
+        }
+        if matches!(self.split.size, Some(size) if size == 0 || size >= 100) {
+            eprintln!("Warning: split.size must be a percentage between 1 and 99");
+        }
         if self.gh_create_on_add && !self.git_init_on_add {
             eprintln!("Warning: gh_create_on_add is enabled but git_init_on_add is disabled.");
             eprintln!("GitHub remote creation requires git initialization. Enable git_init_on_add");
@@ -65,6 +317,23 @@ impl Config {
         }
     }
 
+    pub fn git_status_timeout(&self) -> Duration {
+        Duration::from_millis(self.git_status_timeout_ms)
+    }
+
+    /// Tags from every tag rule matching the given path
+    pub fn rule_tags(&self, path: &Path) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for rule in self.tag_rules.iter().filter(|r| r.matches(path)) {
+            for tag in &rule.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+        }
+        tags
+    }
+
     pub fn save(&self) -> Result<()> {
         let pj_dir = Self::pj_dir()?;
         if !pj_dir.exists() {
@@ -77,5 +346,4 @@ impl Config {
             .with_context(|| format!("Failed to write config file: {}", path.display()))?;
         Ok(())
     }
-
 }
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+
+/// Marker files and the tag they imply. Entries starting with `*.` match any
+/// file with that extension in the project root.
+const MARKERS: &[(&str, &str)] = &[
+    ("Cargo.toml", "rust"),
+    ("package.json", "node"),
+    ("deno.json", "deno"),
+    ("deno.jsonc", "deno"),
+    ("tsconfig.json", "typescript"),
+    ("pyproject.toml", "python"),
+    ("setup.py", "python"),
+    ("requirements.txt", "python"),
+    ("go.mod", "go"),
+    ("go.work", "go"),
+    ("Gemfile", "ruby"),
+    ("composer.json", "php"),
+    ("mix.exs", "elixir"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("build.gradle.kts", "kotlin"),
+    ("Package.swift", "swift"),
+    ("pubspec.yaml", "dart"),
+    ("build.zig", "zig"),
+    ("stack.yaml", "haskell"),
+    ("*.cabal", "haskell"),
+    ("*.csproj", "dotnet"),
+    ("*.fsproj", "dotnet"),
+    ("*.sln", "dotnet"),
+    ("CMakeLists.txt", "cmake"),
+    ("meson.build", "meson"),
+    ("flake.nix", "nix"),
+    ("shell.nix", "nix"),
+    ("default.nix", "nix"),
+    ("Dockerfile", "docker"),
+    ("compose.yaml", "docker"),
+    ("compose.yml", "docker"),
+    ("docker-compose.yaml", "docker"),
+    ("docker-compose.yml", "docker"),
+    ("*.tf", "terraform"),
+    ("Makefile", "make"),
+    ("justfile", "just"),
+    ("Justfile", "just"),
+];
+
+/// Detect language and tooling tags from marker files in the project root
+pub fn detect_tags(path: &Path) -> Vec<String> {
+    let extensions: Vec<String> = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    e.path()
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .map(|ext| ext.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut tags: Vec<String> = Vec::new();
+    for (marker, tag) in MARKERS {
+        let found = match marker.strip_prefix("*.") {
+            Some(ext) => extensions.iter().any(|e| e == ext),
+            None => path.join(marker).is_file(),
+        };
+        if found && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
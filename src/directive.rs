@@ -0,0 +1,128 @@
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::process::Command;
+
+/// Version of the directive protocol spoken with the shell function. Bump it
+/// when the shell function has to change to understand pj's directives.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Set by the shell function to the protocol version it speaks
+const PROTOCOL_VAR: &str = "PJ_SHELL_PROTOCOL";
+
+/// Set by the shell function to the file it reads directives from
+const FILE_VAR: &str = "PJ_DIRECTIVES_FILE";
+
+/// Make sure the shell function can act on pj's directives. Outdated shell
+/// functions capture stdout instead of passing a directives file; when
+/// stdout is a terminal, pj runs without the shell function.
+pub fn check_shell() -> Result<()> {
+    match env::var(PROTOCOL_VAR) {
+        Ok(version) if version == PROTOCOL_VERSION.to_string() => Ok(()),
+        Ok(version) => match version.parse::<u32>() {
+            Ok(version) if version > PROTOCOL_VERSION => bail!(
+                "The pj shell function (protocol {}) is newer than this pj binary (protocol {}). Upgrade pj",
+                version,
+                PROTOCOL_VERSION
+            ),
+            _ => bail!(
+                "The pj shell function is outdated (protocol {}, pj needs {}). Update it with: pj --init",
+                version,
+                PROTOCOL_VERSION
+            ),
+        },
+        Err(_) if io::stdout().is_terminal() => Ok(()),
+        Err(_) => bail!(
+            "The pj shell function is outdated. Update it with: pj --init (scripts should use pj --print)"
+        ),
+    }
+}
+
+/// Have the shell change into `path`
+pub fn cd(path: &Path) -> Result<()> {
+    write("cd", &path.display().to_string())
+}
+
+/// Have the shell run a command line in the foreground
+pub fn eval(command: &str) -> Result<()> {
+    write("eval", command)
+}
+
+/// Have the shell export an environment variable. Without the shell function
+/// there is no shell environment to change, and nothing is printed.
+#[allow(dead_code)] // Part of protocol 1; no command sets variables yet
+pub fn export(name: &str, value: &str) -> Result<()> {
+    if env::var_os(FILE_VAR).is_none() {
+        return Ok(());
+    }
+    write("export", &format!("{}={}", name, value))
+}
+
+/// Have the shell print a line once it has run the preceding directives
+pub fn echo(text: &str) -> Result<()> {
+    write("echo", text)
+}
+
+/// Keep the shell function's variables from a program pj starts, so a pj run
+/// inside it (e.g. from an editor's terminal) does not write into this run's
+/// directives file
+pub fn isolate(command: &mut Command) -> &mut Command {
+    command.env_remove(PROTOCOL_VAR).env_remove(FILE_VAR)
+}
+
+/// Append a directive to the shell function's file, starting it with the
+/// `pj-directives <version>` header. Without the shell function the value is
+/// printed instead.
+fn write(directive: &str, value: &str) -> Result<()> {
+    if value.contains('\n') {
+        bail!("Cannot pass a value containing a newline to the shell: {:?}", value);
+    }
+
+    let Some(file) = env::var_os(FILE_VAR) else {
+        println!("{}", value);
+        return Ok(());
+    };
+
+    let mut lines = String::new();
+    if fs::metadata(&file).map(|m| m.len() == 0).unwrap_or(true) {
+        lines.push_str(&format!("pj-directives {}\n", PROTOCOL_VERSION));
+    }
+    lines.push_str(&format!("{} {}\n", directive, value));
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&file)
+        .and_then(|mut f| f.write_all(lines.as_bytes()))
+        .with_context(|| format!("Failed to write directives to {}", Path::new(&file).display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_directives_after_header() {
+        let file = env::temp_dir().join(format!("pj-test-directives-{}", std::process::id()));
+        fs::write(&file, "").unwrap();
+        env::set_var(FILE_VAR, &file);
+
+        cd(Path::new("/work/my api")).unwrap();
+        eval("nvim .").unwrap();
+        export("NAME", "a b").unwrap();
+        echo("done").unwrap();
+        let rejected = echo("two\nlines").is_err();
+
+        env::remove_var(FILE_VAR);
+        let written = fs::read_to_string(&file).unwrap();
+        fs::remove_file(&file).unwrap();
+
+        assert!(rejected);
+        assert_eq!(
+            written,
+            "pj-directives 1\ncd /work/my api\neval nvim .\nexport NAME=a b\necho done\n"
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub struct Worktree {
+    pub path: PathBuf,
+    pub branch: String,
+}
+
+pub fn is_git_repo(path: &Path) -> bool {
+    path.join(".git").exists()
+}
+
+fn git_output(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to run git {}", args.join(" ")))?;
+
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Main working tree (or bare repository) of the repository containing
+/// `repo`, which differs from `repo` when it is a linked worktree
+pub fn main_worktree(repo: &Path) -> Result<PathBuf> {
+    let output = git_output(repo, &["worktree", "list", "--porcelain"])?;
+    let path = output
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("worktree "))
+        .map(PathBuf::from)
+        .context("Failed to read the main worktree")?;
+    Ok(path.canonicalize().unwrap_or(path))
+}
+
+/// Linked worktrees of a repository, excluding the main working tree
+pub fn list_worktrees(repo: &Path) -> Result<Vec<Worktree>> {
+    let output = git_output(repo, &["worktree", "list", "--porcelain"])?;
+
+    let mut worktrees = Vec::new();
+    // Entries are separated by blank lines; the first entry is the main tree
+    for entry in output.split("\n\n").skip(1) {
+        let mut path = None;
+        let mut branch = None;
+        let mut skip = false;
+        for line in entry.lines() {
+            if let Some(p) = line.strip_prefix("worktree ") {
+                path = Some(PathBuf::from(p));
+            } else if let Some(b) = line.strip_prefix("branch ") {
+                branch = Some(b.trim_start_matches("refs/heads/").to_string());
+            } else if line == "detached" {
+                branch = Some("(detached)".to_string());
+            } else if line == "bare" || line.starts_with("prunable") {
+                skip = true;
+            }
+        }
+        if skip {
+            continue;
+        }
+        if let (Some(path), Some(branch)) = (path, branch) {
+            let path = path.canonicalize().unwrap_or(path);
+            worktrees.push(Worktree { path, branch });
+        }
+    }
+    Ok(worktrees)
+}
+
+fn branch_exists(repo: &Path, branch: &str) -> bool {
+    let reference = format!("refs/heads/{}", branch);
+    git_output(repo, &["show-ref", "--verify", "--quiet", &reference]).is_ok()
+}
+
+/// Create a worktree for `branch` at `path`, creating the branch from HEAD if
+/// it does not exist yet
+pub fn add_worktree(repo: &Path, path: &Path, branch: &str) -> Result<()> {
+    let path_str = path.display().to_string();
+    if branch_exists(repo, branch) {
+        git_output(repo, &["worktree", "add", &path_str, branch])?;
+    } else {
+        git_output(repo, &["worktree", "add", "-b", branch, &path_str])?;
+    }
+    Ok(())
+}
+
+pub fn remove_worktree(repo: &Path, path: &Path) -> Result<()> {
+    git_output(repo, &["worktree", "remove", &path.display().to_string()])?;
+    Ok(())
+}
+
+pub fn current_branch(repo: &Path) -> Option<String> {
+    git_output(repo, &["rev-parse", "--abbrev-ref", "HEAD"])
+        .ok()
+        .map(|b| b.trim().to_string())
+}
+
+/// Changed files in `git status --porcelain` format
+pub fn status_lines(repo: &Path) -> Result<Vec<String>> {
+    let output = git_output(repo, &["status", "--porcelain"])?;
+    Ok(output.lines().map(|l| l.to_string()).collect())
+}
+
+/// One-line summaries of the most recent commits
+pub fn recent_commits(repo: &Path, count: usize) -> Vec<String> {
+    let count = format!("-{}", count);
+    git_output(repo, &["log", &count, "--format=%h %s (%cr)"])
+        .map(|out| out.lines().map(|l| l.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Working tree summary shown in the picker and `--list`
+#[derive(Debug, Clone)]
+pub struct RepoStatus {
+    pub branch: String,
+    pub dirty: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    /// Unix timestamp of the last commit
+    pub last_commit: Option<i64>,
+}
+
+impl RepoStatus {
+    /// `clean`/`dirty` followed by ahead/behind counts, e.g. `dirty ↑2 ↓1`
+    pub fn state(&self) -> String {
+        let mut state = if self.dirty { "dirty" } else { "clean" }.to_string();
+        if self.ahead > 0 {
+            state.push_str(&format!(" ↑{}", self.ahead));
+        }
+        if self.behind > 0 {
+            state.push_str(&format!(" ↓{}", self.behind));
+        }
+        state
+    }
+
+    /// Age of the last commit, e.g. `3d`
+    pub fn last_commit_age(&self) -> String {
+        let Some(timestamp) = self.last_commit else {
+            return "-".to_string();
+        };
+        let secs = (chrono::Utc::now().timestamp() - timestamp).max(0);
+        match secs {
+            s if s < 3600 => format!("{}m", s / 60),
+            s if s < 86400 => format!("{}h", s / 3600),
+            s if s < 86400 * 30 => format!("{}d", s / 86400),
+            s if s < 86400 * 365 => format!("{}mo", s / (86400 * 30)),
+            s => format!("{}y", s / (86400 * 365)),
+        }
+    }
+}
+
+/// Branch, dirty state, upstream divergence and last commit time of a repository
+pub fn repo_status(repo: &Path) -> Option<RepoStatus> {
+    if !is_git_repo(repo) {
+        return None;
+    }
+    let output = git_output(repo, &["status", "--porcelain=v2", "--branch"]).ok()?;
+
+    let mut status = RepoStatus {
+        branch: "(detached)".to_string(),
+        dirty: false,
+        ahead: 0,
+        behind: 0,
+        last_commit: None,
+    };
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            if head != "(detached)" {
+                status.branch = head.to_string();
+            }
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            for count in ab.split_whitespace() {
+                if let Some(n) = count.strip_prefix('+') {
+                    status.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = count.strip_prefix('-') {
+                    status.behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if !line.starts_with('#') {
+            status.dirty = true;
+        }
+    }
+
+    status.last_commit = git_output(repo, &["log", "-1", "--format=%ct"])
+        .ok()
+        .and_then(|out| out.trim().parse().ok());
+    Some(status)
+}
+
+/// Number of repositories queried at once by `repo_statuses`
+const STATUS_WORKERS: usize = 8;
+
+/// Query `repo_status` for many repositories in parallel. Repositories that
+/// have not answered when `timeout` runs out are left out of the result.
+pub fn repo_statuses(paths: &[PathBuf], timeout: Duration) -> HashMap<PathBuf, RepoStatus> {
+    let deadline = Instant::now() + timeout;
+    let queue = Arc::new(Mutex::new(paths.to_vec()));
+    let (tx, rx) = mpsc::channel();
+
+    for _ in 0..STATUS_WORKERS.min(paths.len()) {
+        let queue = Arc::clone(&queue);
+        let tx = tx.clone();
+        thread::spawn(move || {
+            // The guard is dropped inside `and_then`, so git runs unlocked
+            while let Some(path) = queue.lock().ok().and_then(|mut q| q.pop()) {
+                let status = repo_status(&path);
+                if tx.send((path, status)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(tx);
+
+    let mut statuses = HashMap::new();
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(remaining) {
+            Ok((path, Some(status))) => {
+                statuses.insert(path, status);
+            }
+            Ok((_, None)) => {}
+            Err(_) => break,
+        }
+    }
+    // Workers still running git for slow repositories stop at their next send
+    if let Ok(mut queue) = queue.lock() {
+        queue.clear();
+    }
+    statuses
+}
@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Match a single path component against a pattern supporting `*` (any run
+/// of characters) and `?` (any single character)
+pub fn match_component(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Match a path against a glob pattern where `**` matches any number of
+/// directories (including none)
+pub fn matches_path(pattern: &str, path: &Path) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+    let path_str = path.display().to_string();
+    let components: Vec<&str> = path_str.split('/').filter(|c| !c.is_empty()).collect();
+    match_components(&pattern, &components)
+}
+
+fn match_components(pattern: &[&str], components: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => components.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=components.len()).any(|skip| match_components(rest, &components[skip..]))
+        }
+        Some((first, rest)) => match components.split_first() {
+            Some((component, remaining)) => {
+                match_component(first, component) && match_components(rest, remaining)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_tilde(pattern: &str) -> String {
+    if let Some(rest) = pattern.strip_prefix('~') {
+        if rest.is_empty() || rest.starts_with('/') {
+            if let Some(home) = dirs::home_dir() {
+                return format!("{}{}", home.display(), rest);
+            }
+        }
+    }
+    pattern.to_string()
+}
+
+/// Directories skipped when expanding `**`
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target", "vendor"];
+
+/// Expand a relative glob pattern into the directories it matches under `root`
+pub fn expand_dirs(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let components: Vec<&str> = pattern
+        .split('/')
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect();
+    let mut dirs = Vec::new();
+    expand_components(root.to_path_buf(), &components, &mut dirs);
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+fn expand_components(dir: PathBuf, components: &[&str], out: &mut Vec<PathBuf>) {
+    let Some((first, rest)) = components.split_first() else {
+        if dir.is_dir() {
+            out.push(dir);
+        }
+        return;
+    };
+
+    if !first.contains(['*', '?']) {
+        expand_components(dir.join(first), rest, out);
+        return;
+    }
+
+    let subdirs: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter(|e| {
+                    let name = e.file_name();
+                    !SKIPPED_DIRS.contains(&name.to_string_lossy().as_ref())
+                })
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if *first == "**" {
+        expand_components(dir.clone(), rest, out);
+        for subdir in subdirs {
+            expand_components(subdir, components, out);
+        }
+        return;
+    }
+
+    for subdir in subdirs {
+        let matched = subdir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| match_component(first, name));
+        if matched {
+            expand_components(subdir, rest, out);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::directive;
+
+/// Placeholders replaced with the project path in editor and AI assistant
+/// commands; `{file}` is an alias of `{path}`
+const PATH_PLACEHOLDERS: &[&str] = &["{path}", "{file}"];
+
+/// Placeholder replaced with the project directory name
+const NAME_PLACEHOLDER: &str = "{name}";
+
+/// An editor or AI assistant command from config, split into shell words,
+/// e.g. `emacsclient -c -a ''` or `code -n {path}`
+#[derive(Debug, Clone)]
+pub struct CommandTemplate {
+    words: Vec<String>,
+}
+
+impl CommandTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let words = shell_words::split(template)
+            .with_context(|| format!("Invalid command '{}'", template))?;
+        if words.is_empty() {
+            bail!("Command is empty");
+        }
+        Ok(Self { words })
+    }
+
+    pub fn program(&self) -> &str {
+        &self.words[0]
+    }
+
+    /// Whether this is one of the configured terminal editors. An entry
+    /// matches on the program's file name, and any further words in the
+    /// entry (such as `-nw` in `emacs -nw`) must appear among the arguments.
+    pub fn is_terminal(&self, terminal_editors: &[String]) -> bool {
+        let program = Path::new(self.program())
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        terminal_editors.iter().any(|entry| {
+            let mut words = entry.split_whitespace();
+            words.next() == Some(program)
+                && words.all(|word| self.words[1..].iter().any(|arg| arg == word))
+        })
+    }
+
+    /// Arguments with the placeholders replaced. Editors get the path
+    /// appended when the template has no placeholder.
+    fn args(&self, path: &Path, append_path: bool) -> Vec<String> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path = path.display().to_string();
+        let mut has_placeholder = false;
+        let mut args: Vec<String> = self.words[1..]
+            .iter()
+            .map(|word| {
+                let mut arg = word.replace(NAME_PLACEHOLDER, &name);
+                for placeholder in PATH_PLACEHOLDERS {
+                    if arg.contains(placeholder) {
+                        has_placeholder = true;
+                        arg = arg.replace(placeholder, &path);
+                    }
+                }
+                arg
+            })
+            .collect();
+        if append_path && !has_placeholder {
+            args.push(path);
+        }
+        args
+    }
+
+    /// Editor process opening `path`
+    fn editor_command(&self, path: &Path) -> Command {
+        let mut command = Command::new(self.program());
+        directive::isolate(&mut command).args(self.args(path, true));
+        command
+    }
+
+    /// Start a GUI editor in the background, detached from pj's stdout so
+    /// the shell function does not wait for it
+    pub fn spawn_editor(&self, path: &Path) -> Result<()> {
+        self.editor_command(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to launch editor '{}'", self.program()))?;
+        Ok(())
+    }
+
+    /// Editor command line opening `path`, for multiplexer panes and the
+    /// shell function
+    pub fn editor_line(&self, path: &Path) -> String {
+        self.shell_line(path, true)
+    }
+
+    /// AI assistant command line; it runs inside the project, so the path is
+    /// only passed where the template asks for it
+    pub fn ai_line(&self, path: &Path) -> String {
+        self.shell_line(path, false)
+    }
+
+    fn shell_line(&self, path: &Path, append_path: bool) -> String {
+        let mut words = vec![self.program().to_string()];
+        words.extend(self.args(path, append_path));
+        shell_words::join(words)
+    }
+}
+
+/// Check an editor or AI assistant setting; `none` disables it
+pub fn validate(label: &str, template: &str) -> Result<()> {
+    if template == "none" {
+        return Ok(());
+    }
+    CommandTemplate::parse(template).with_context(|| format!("Invalid {} command", label))?;
+    Ok(())
+}
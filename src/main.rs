@@ -1,11 +1,19 @@
 mod commands;
 mod config;
+mod detect;
+mod directive;
 mod frecency;
+mod git;
 mod github;
+mod glob;
+mod launch;
 mod multiplexer;
+mod picker;
+mod preview;
 mod projects;
 mod shell;
 mod tui;
+mod workspace;
 
 use clap::Parser;
 use std::path::PathBuf;
@@ -19,10 +27,34 @@ struct Cli {
     #[arg(value_name = "FILTER")]
     filters: Vec<String>,
 
+    /// Print the best matching project path and exit (no TUI, editor or frecency update)
+    #[arg(long = "print", conflicts_with_all = ["add", "init", "list", "config"])]
+    print: bool,
+
+    /// Print all matching project paths in ranked order and exit
+    #[arg(long = "print-all", conflicts_with_all = ["add", "init", "list", "config", "print"])]
+    print_all: bool,
+
     /// Add current directory to tracked projects
     #[arg(short = 'a', long = "add")]
     add: bool,
 
+    /// Clone a repository into the configured layout and track it
+    #[arg(long = "clone", value_name = "URL", conflicts_with_all = ["add", "init", "list", "config"])]
+    clone: Option<String>,
+
+    /// Open the project after --clone
+    #[arg(long = "open", requires = "clone")]
+    open: bool,
+
+    /// Create a new project from a template, track it and open it
+    #[arg(long = "new", value_name = "NAME", conflicts_with_all = ["add", "init", "list", "config", "clone"])]
+    new: Option<String>,
+
+    /// Template for --new (prompted when several are configured)
+    #[arg(long = "template", value_name = "TEMPLATE", requires = "new")]
+    template: Option<String>,
+
     /// Open skim (multi-select) to remove projects
     #[arg(long = "rm", conflicts_with_all = ["add", "init", "list", "rm_missing"])]
     rm: bool,
@@ -31,7 +63,7 @@ struct Cli {
     #[arg(long = "rm-missing", conflicts_with_all = ["add", "init", "list", "rm"])]
     rm_missing: bool,
 
-    /// Add tags to project(s). Format: -t tag1,tag2 [path]
+    /// Add tags to project(s). Format: -t tag1,key=value [path]
     /// When used with -a, adds tags to the newly added project
     #[arg(short = 't', long = "tags", value_name = "TAGS", num_args = 0..=1)]
     tags: Option<Option<String>>,
@@ -56,6 +88,14 @@ struct Cli {
     #[arg(long = "no-ai", conflicts_with = "ai")]
     no_ai: bool,
 
+    /// Start the AI assistant in its own multiplexer pane
+    #[arg(long = "ai-pane", conflicts_with = "no_ai_pane")]
+    ai_pane: bool,
+
+    /// Start the AI assistant in the current shell
+    #[arg(long = "no-ai-pane", conflicts_with = "ai_pane")]
+    no_ai_pane: bool,
+
     /// Force cd to selected project
     #[arg(long = "cd", conflicts_with = "no_cd")]
     force_cd: bool,
@@ -72,6 +112,94 @@ struct Cli {
     #[arg(long = "list", conflicts_with_all = ["add", "rm", "rm_missing", "init", "config"])]
     list: bool,
 
+    /// Group --list output by the values of a key=value tag key
+    #[arg(long = "group-by", value_name = "KEY", requires = "list")]
+    group_by: Option<String>,
+
+    /// Print the picker preview of a project (used by the fzf picker)
+    #[arg(long = "preview-path", value_name = "PATH", hide = true)]
+    preview_path: Option<PathBuf>,
+
+    /// Print the picker preview of a group instead (used by the fzf picker)
+    #[arg(long = "preview-group", value_name = "NAME", requires = "preview_path", hide = true)]
+    preview_group: Option<String>,
+
+    /// Show git status columns in --list (also enabled by `git_status` in config)
+    #[arg(long = "git-status", requires = "list")]
+    git_status: bool,
+
+    /// List all tags with the number of projects using each
+    #[arg(long = "list-tags", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config"])]
+    list_tags: bool,
+
+    /// Rename a tag across all projects. Format: --rename-tag old new
+    #[arg(long = "rename-tag", num_args = 2, value_names = ["OLD", "NEW"], conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "list_tags"])]
+    rename_tag: Option<Vec<String>>,
+
+    /// Merge tags into one across all projects. Format: --merge-tags tag1,tag2 target
+    #[arg(long = "merge-tags", num_args = 2, value_names = ["TAGS", "TARGET"], conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "list_tags", "rename_tag"])]
+    merge_tags: Option<Vec<String>>,
+
+    /// Delete tags from all projects. Format: --delete-tags tag1,tag2
+    #[arg(long = "delete-tags", value_name = "TAGS", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "list_tags", "rename_tag", "merge_tags"])]
+    delete_tags: Option<String>,
+
+    /// Re-detect auto tags (language/tooling) for all projects
+    #[arg(long = "refresh-auto-tags", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config"])]
+    refresh_auto_tags: bool,
+
+    /// Apply tag rules from config to all tracked projects
+    #[arg(long = "apply-tag-rules", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config"])]
+    apply_tag_rules: bool,
+
+    /// Discover git worktrees of all tracked projects
+    #[arg(long = "sync-worktrees", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config"])]
+    sync_worktrees: bool,
+
+    /// Discover workspace members (Cargo, pnpm, npm/yarn, go.work) of all tracked projects
+    #[arg(long = "sync-members", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "sync_worktrees"])]
+    sync_members: bool,
+
+    /// Create a git worktree for BRANCH in the current project and track it
+    #[arg(long = "worktree-add", value_name = "BRANCH", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "sync_worktrees"])]
+    worktree_add: Option<String>,
+
+    /// Remove the git worktree for BRANCH in the current project
+    #[arg(long = "worktree-rm", value_name = "BRANCH", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "sync_worktrees", "worktree_add"])]
+    worktree_rm: Option<String>,
+
+    /// Run a launch profile from config in the selected project instead of the editor and AI assistant
+    #[arg(long = "run", value_name = "PROFILE", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config"])]
+    run: Option<String>,
+
+    /// Switch to the selected project's tmux session, creating it if needed
+    #[arg(long = "session", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "run"])]
+    session: bool,
+
+    /// Set whether the current (or given) project starts the AI assistant in its own pane
+    #[arg(long = "set-ai-pane", num_args = 1..=2, value_names = ["SETTING", "PATH"], conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config"])]
+    set_ai_pane: Option<Vec<String>>,
+
+    /// Add projects to a group (created if needed); picks them when no PATH is given
+    #[arg(long = "group-add", num_args = 1.., value_names = ["NAME", "PATH"], conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config"])]
+    group_add: Option<Vec<String>>,
+
+    /// Set the directory a group changes into (the current directory when no PATH is given)
+    #[arg(long = "group-cd", num_args = 1..=2, value_names = ["NAME", "PATH"], conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "group_add"])]
+    group_cd: Option<Vec<String>>,
+
+    /// Delete a group (its projects stay tracked)
+    #[arg(long = "group-rm", value_name = "NAME", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "group_add"])]
+    group_rm: Option<String>,
+
+    /// List project groups
+    #[arg(long = "list-groups", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "config", "group_add", "group_rm"])]
+    list_groups: bool,
+
+    /// Preview bulk tag changes and tag rules without saving them
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
     /// Show current configuration
     #[arg(long = "config", conflicts_with_all = ["add", "rm", "rm_missing", "init", "list", "tags", "rm_tags"])]
     config: bool,
@@ -80,51 +208,103 @@ struct Cli {
 fn main() {
     let cli = Cli::parse();
 
-    let result = if cli.init {
+    // Per-invocation launch overrides, used by selection and --clone --open
+    let cd_override = if cli.force_cd {
+        Some(true)
+    } else if cli.no_cd {
+        Some(false)
+    } else {
+        None
+    };
+    let editor_override = if cli.no_editor {
+        Some(String::new())
+    } else {
+        cli.editor
+    };
+    let ai_override = if cli.no_ai {
+        Some(String::new())
+    } else {
+        cli.ai
+    };
+    let overrides = commands::select::LaunchOverrides {
+        editor: editor_override,
+        cd: cd_override,
+        ai: ai_override,
+        profile: cli.run,
+        session: cli.session.then_some(true),
+        ai_pane: if cli.ai_pane {
+            Some(true)
+        } else if cli.no_ai_pane {
+            Some(false)
+        } else {
+            None
+        },
+    };
+
+    let result = if let Some(path) = cli.preview_path {
+        preview::run(&path, cli.preview_group.as_deref().filter(|g| !g.is_empty()))
+    } else if cli.init {
         commands::init::run()
     } else if cli.config {
         commands::show_config::run()
     } else if cli.list {
-        commands::list::run()
+        commands::list::run(cli.group_by, cli.git_status)
+    } else if cli.print || cli.print_all {
+        commands::print::run(cli.filters, cli.print_all)
+    } else if cli.list_tags {
+        commands::tag::list_all()
+    } else if cli.list_groups {
+        commands::group::list()
+    } else if let Some(args) = cli.group_add {
+        commands::group::add(&args[0], &args[1..])
+    } else if let Some(args) = cli.group_cd {
+        commands::group::set_cd(&args[0], args.get(1).map(String::as_str))
+    } else if let Some(name) = cli.group_rm {
+        commands::group::remove(&name)
+    } else if let Some(args) = cli.rename_tag {
+        commands::tag::rename(&args[0], &args[1], cli.dry_run)
+    } else if let Some(args) = cli.merge_tags {
+        commands::tag::merge(&args[0], &args[1], cli.dry_run)
+    } else if let Some(tags) = cli.delete_tags {
+        commands::tag::delete(&tags, cli.dry_run)
+    } else if cli.refresh_auto_tags {
+        commands::tag::refresh_auto()
+    } else if cli.apply_tag_rules {
+        commands::tag::apply_rules(cli.dry_run)
+    } else if cli.sync_worktrees {
+        commands::worktree::sync()
+    } else if cli.sync_members {
+        commands::members::sync()
+    } else if let Some(args) = cli.set_ai_pane {
+        commands::ai_pane::set(&args[0], args.get(1).map(PathBuf::from))
+    } else if let Some(branch) = cli.worktree_add {
+        commands::worktree::add(&branch)
+    } else if let Some(branch) = cli.worktree_rm {
+        commands::worktree::remove(&branch)
     } else if cli.rm {
         commands::rm::run(false)
     } else if cli.rm_missing {
         commands::rm::run(true)
+    } else if let Some(url) = cli.clone {
+        commands::clone::run(&url, cli.tags.flatten(), cli.open, overrides)
+    } else if let Some(name) = cli.new {
+        commands::new::run(&name, cli.template.as_deref(), cli.tags.flatten(), overrides)
     } else if cli.add {
         // -a/--add: Add current directory
         // If -t is also present, those are tags for the new project
         let tags_for_add = cli.tags.flatten();
         commands::add::run(tags_for_add)
-    } else if cli.tags.is_some() {
+    } else if let Some(tags_value) = cli.tags {
         // --tags without --add: tag management operation
-        let tags_value = cli.tags.unwrap();
-        let path = cli.filters.first().map(|s| PathBuf::from(s));
+        let path = cli.filters.first().map(PathBuf::from);
         commands::tag::run(tags_value, path, false)
-    } else if cli.rm_tags.is_some() {
+    } else if let Some(tags_value) = cli.rm_tags {
         // --rm-tags: remove tags operation
-        let tags_value = cli.rm_tags.unwrap();
-        let path = cli.filters.first().map(|s| PathBuf::from(s));
+        let path = cli.filters.first().map(PathBuf::from);
         commands::tag::run(tags_value, path, true)
     } else {
         // Project selection mode
-        let cd_override = if cli.force_cd {
-            Some(true)
-        } else if cli.no_cd {
-            Some(false)
-        } else {
-            None
-        };
-        let editor_override = if cli.no_editor {
-            Some(String::new())
-        } else {
-            cli.editor
-        };
-        let ai_override = if cli.no_ai {
-            Some(String::new())
-        } else {
-            cli.ai
-        };
-        commands::select::run(cli.filters, editor_override, cd_override, ai_override)
+        commands::select::run(cli.filters, overrides)
     };
 
     if let Err(e) = result {
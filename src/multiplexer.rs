@@ -1,103 +1,790 @@
-use std::path::Path;
-use std::process::Command;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-enum Multiplexer {
-    Tmux,
-    Cmux,
+use crate::config::{Config, SplitDirection, SplitLayout};
+use crate::directive;
+
+/// A terminal multiplexer pj can open panes in
+trait Backend {
+    fn name(&self) -> &'static str;
+
+    /// Run a terminal editor command in `path` next to the current pane
+    fn open_split(&self, command: &str, path: &Path, layout: &SplitLayout) -> Result<(), String>;
+
+    /// Run a command in `path` in a new window, tab or pane, leaving focus on
+    /// the current pane where the multiplexer allows it
+    fn open_window(&self, command: &str, path: &Path) -> Result<(), String>;
 }
 
-fn detect_multiplexer() -> Option<Multiplexer> {
-    if std::env::var("CMUX_SOCKET").is_ok() || std::env::var("CMUX_SOCKET_PATH").is_ok() {
-        return Some(Multiplexer::Cmux);
+/// The multiplexer pj runs inside. A multiplexer started inside another
+/// inherits its variables, so when several are set the first in this order
+/// wins: cmux and tmux, which are usually started from within a terminal's
+/// mux, come first (Zellij started inside tmux is therefore taken for tmux).
+/// kitty is only driven through a `listen_on` socket, as remote control over
+/// the tty needs pj's terminal.
+fn detect_multiplexer(config: &Config) -> Option<Box<dyn Backend>> {
+    let set = |var: &str| env::var_os(var).is_some();
+    if set("CMUX_SOCKET") || set("CMUX_SOCKET_PATH") {
+        Some(Box::new(Cmux {
+            ready_timeout: config.split.cmux_ready_timeout(),
+        }))
+    } else if set("TMUX") {
+        Some(Box::new(Tmux))
+    } else if set("ZELLIJ") {
+        Some(Box::new(Zellij))
+    } else if set("WEZTERM_PANE") {
+        Some(Box::new(WezTerm))
+    } else if let Some(listen_on) = env::var_os("KITTY_LISTEN_ON") {
+        Some(Box::new(Kitty {
+            listen_on: listen_on.to_string_lossy().into_owned(),
+        }))
+    } else {
+        None
     }
-    if std::env::var("TMUX").is_ok() {
-        return Some(Multiplexer::Tmux);
+}
+
+/// Run a multiplexer CLI command and return its stdout
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = directive::isolate(&mut Command::new(program))
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run {} {}: {}", program, args[0], e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{} {} failed: {}",
+            program,
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
     }
-    None
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
-fn is_terminal_editor(editor: &str) -> bool {
-    Path::new(editor)
-        .file_name()
-        .and_then(|name| name.to_str())
-        .map(|name| name == "vim" || name == "nvim")
-        .unwrap_or(false)
+/// Window or tab title for a project
+fn window_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
 }
 
-fn open_in_tmux_split(editor: &str, path: &Path) -> Result<(), String> {
-    let path_str = path.display().to_string();
-    let cmd = format!("{} \"{}\"", editor, path_str);
+struct Tmux;
 
-    Command::new("tmux")
-        .args(["split-window", "-h", "-d", &cmd])
-        .status()
-        .map_err(|e| format!("Failed to run tmux split-window: {}", e))?;
+impl Tmux {
+    fn new_window(&self, command: &str, path: &Path, focus: bool) -> Result<(), String> {
+        let path_str = path.display().to_string();
+        let name = window_name(path);
 
-    Ok(())
+        let mut args = vec!["new-window"];
+        if !focus {
+            args.push("-d");
+        }
+        args.extend(["-n", &name, "-c", &path_str, command]);
+        run("tmux", &args).map(drop)
+    }
 }
 
-fn open_in_cmux_split(editor: &str, path: &Path) -> Result<(), String> {
-    let output = Command::new("cmux")
-        .args(["new-split", "right"])
-        .output()
-        .map_err(|e| format!("Failed to run cmux new-split: {}", e))?;
+impl Backend for Tmux {
+    fn name(&self) -> &'static str {
+        "tmux"
+    }
 
-    if !output.status.success() {
-        return Err(format!(
-            "cmux new-split failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+    fn open_split(&self, command: &str, path: &Path, layout: &SplitLayout) -> Result<(), String> {
+        if layout.window {
+            return self.new_window(command, path, layout.focus);
+        }
+
+        let path_str = path.display().to_string();
+        let size = layout.size.map(|size| format!("{}%", size));
+        let mut args = vec!["split-window"];
+        args.push(match layout.direction {
+            SplitDirection::Horizontal => "-h",
+            SplitDirection::Vertical => "-v",
+        });
+        if let Some(size) = &size {
+            args.extend(["-l", size]);
+        }
+        if !layout.focus {
+            args.push("-d");
+        }
+        args.extend(["-c", &path_str, command]);
+        run("tmux", &args).map(drop)
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let surface_ref = stdout
-        .split_whitespace()
-        .find(|token| token.starts_with("surface:"))
-        .ok_or_else(|| format!("Failed to parse surface ref from cmux output: {}", stdout))?
-        .to_string();
+    fn open_window(&self, command: &str, path: &Path) -> Result<(), String> {
+        self.new_window(command, path, false)
+    }
+}
 
-    // Wait for the new pane's shell to initialize before sending keystrokes
-    thread::sleep(Duration::from_millis(200));
+/// cmux supports `direction` only: it has no sizes and always focuses the
+/// new split
+struct Cmux {
+    /// How long to wait for the new pane's shell to draw its prompt
+    ready_timeout: Duration,
+}
 
-    let path_str = path.display().to_string();
-    let send_cmd = format!("{} \"{}\"\\n", editor, path_str);
+/// Interval between checks of a new cmux pane's screen
+const CMUX_POLL_INTERVAL: Duration = Duration::from_millis(25);
 
-    let send_output = Command::new("cmux")
-        .args(["send", "--surface", &surface_ref, "--", &send_cmd])
-        .output()
-        .map_err(|e| format!("Failed to run cmux send: {}", e))?;
+/// Delay before typing into a new pane when cmux cannot read its screen
+const CMUX_FALLBACK_DELAY: Duration = Duration::from_millis(200);
 
-    if !send_output.status.success() {
-        return Err(format!(
-            "cmux send failed: {}",
-            String::from_utf8_lossy(&send_output.stderr)
-        ));
+impl Cmux {
+    /// Open a split and type a command into its shell, in `path`: the new
+    /// pane starts in pj's directory
+    fn run_in_split(&self, command: &str, path: &Path, direction: &str) -> Result<(), String> {
+        let path = path.display().to_string();
+        let command = format!("cd {} && {}", shell_words::quote(&path), command);
+        let stdout = run("cmux", &["new-split", direction])?;
+        let surface_ref = stdout
+            .split_whitespace()
+            .find(|token| token.starts_with("surface:"))
+            .ok_or_else(|| format!("Failed to parse surface ref from cmux output: {}", stdout))?
+            .to_string();
+
+        self.wait_until_ready(&surface_ref);
+
+        // cmux send expands backslash escapes: keep the command's backslashes
+        // and end it with an escaped newline
+        let send_cmd = format!("{}\\n", command.replace('\\', "\\\\"));
+        run("cmux", &["send", "--surface", &surface_ref, "--", &send_cmd]).map(drop)
     }
 
-    Ok(())
+    /// Wait until the pane's shell has drawn something (its prompt), so the
+    /// keystrokes are not typed before the shell reads input. Sends anyway
+    /// after the timeout.
+    fn wait_until_ready(&self, surface_ref: &str) {
+        let deadline = Instant::now() + self.ready_timeout;
+        loop {
+            match run("cmux", &["read-screen", "--surface", surface_ref]) {
+                Ok(screen) if !screen.trim().is_empty() => return,
+                Ok(_) => {}
+                // Older cmux without read-screen
+                Err(_) => {
+                    thread::sleep(CMUX_FALLBACK_DELAY.min(self.ready_timeout));
+                    return;
+                }
+            }
+            if Instant::now() >= deadline {
+                eprintln!(
+                    "Warning: cmux pane {} not ready after {}ms",
+                    surface_ref,
+                    self.ready_timeout.as_millis()
+                );
+                return;
+            }
+            thread::sleep(CMUX_POLL_INTERVAL);
+        }
+    }
 }
 
-pub fn try_open_in_split(editor: &str, path: &Path) -> bool {
-    if !is_terminal_editor(editor) {
-        return false;
+impl Backend for Cmux {
+    fn name(&self) -> &'static str {
+        "cmux"
+    }
+
+    fn open_split(&self, command: &str, path: &Path, layout: &SplitLayout) -> Result<(), String> {
+        let direction = match layout.direction {
+            SplitDirection::Horizontal => "right",
+            SplitDirection::Vertical => "down",
+        };
+        self.run_in_split(command, path, direction)
+    }
+
+    fn open_window(&self, command: &str, path: &Path) -> Result<(), String> {
+        self.run_in_split(command, path, "right")
+    }
+}
+
+/// Zellij panes always take focus and have no size option; `window` opens a
+/// pane in the free space instead of a split
+struct Zellij;
+
+impl Zellij {
+    fn new_pane(&self, command: &str, path: &Path, direction: Option<&str>) -> Result<(), String> {
+        let path_str = path.display().to_string();
+        let name = window_name(path);
+
+        let mut args = vec!["action", "new-pane"];
+        if let Some(direction) = direction {
+            args.extend(["--direction", direction]);
+        }
+        args.extend(["--cwd", &path_str, "--name", &name, "--close-on-exit"]);
+        args.extend(["--", "sh", "-c", command]);
+        run("zellij", &args).map(drop)
+    }
+}
+
+impl Backend for Zellij {
+    fn name(&self) -> &'static str {
+        "Zellij"
+    }
+
+    fn open_split(&self, command: &str, path: &Path, layout: &SplitLayout) -> Result<(), String> {
+        let direction = match (layout.window, layout.direction) {
+            (true, _) => None,
+            (false, SplitDirection::Horizontal) => Some("right"),
+            (false, SplitDirection::Vertical) => Some("down"),
+        };
+        self.new_pane(command, path, direction)
+    }
+
+    fn open_window(&self, command: &str, path: &Path) -> Result<(), String> {
+        self.new_pane(command, path, None)
+    }
+}
+
+/// WezTerm's built-in multiplexer; `window` opens a tab
+struct WezTerm;
+
+impl WezTerm {
+    /// Give focus back to the pane pj runs in
+    fn refocus(&self) -> Result<(), String> {
+        match env::var("WEZTERM_PANE") {
+            Ok(pane) => run("wezterm", &["cli", "activate-pane", "--pane-id", &pane]).map(drop),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn spawn_tab(&self, command: &str, path: &Path, focus: bool) -> Result<(), String> {
+        let path_str = path.display().to_string();
+        run(
+            "wezterm",
+            &["cli", "spawn", "--cwd", &path_str, "--", "sh", "-c", command],
+        )?;
+        if focus {
+            Ok(())
+        } else {
+            self.refocus()
+        }
+    }
+}
+
+impl Backend for WezTerm {
+    fn name(&self) -> &'static str {
+        "WezTerm"
+    }
+
+    fn open_split(&self, command: &str, path: &Path, layout: &SplitLayout) -> Result<(), String> {
+        if layout.window {
+            return self.spawn_tab(command, path, layout.focus);
+        }
+
+        let path_str = path.display().to_string();
+        let percent = layout.size.map(|size| size.to_string());
+        let mut args = vec!["cli", "split-pane"];
+        args.push(match layout.direction {
+            SplitDirection::Horizontal => "--right",
+            SplitDirection::Vertical => "--bottom",
+        });
+        if let Some(percent) = &percent {
+            args.extend(["--percent", percent]);
+        }
+        args.extend(["--cwd", &path_str, "--", "sh", "-c", command]);
+        run("wezterm", &args)?;
+
+        if layout.focus {
+            Ok(())
+        } else {
+            self.refocus()
+        }
+    }
+
+    fn open_window(&self, command: &str, path: &Path) -> Result<(), String> {
+        self.spawn_tab(command, path, false)
+    }
+}
+
+/// kitty remote control (`allow_remote_control` and `listen_on` must be
+/// set); `window` opens a tab, and `size` is passed as the split bias
+struct Kitty {
+    /// Remote control socket from `KITTY_LISTEN_ON`
+    listen_on: String,
+}
+
+impl Kitty {
+    fn launch(&self, options: &[&str], command: &str, path: &Path) -> Result<(), String> {
+        let cwd = format!("--cwd={}", path.display());
+        let title = format!("--title={}", window_name(path));
+
+        let mut args = vec!["@", "--to", &self.listen_on, "launch", &cwd, &title];
+        args.extend(options);
+        args.extend(["sh", "-c", command]);
+        run("kitty", &args).map(drop)
+    }
+}
+
+impl Backend for Kitty {
+    fn name(&self) -> &'static str {
+        "kitty"
+    }
+
+    fn open_split(&self, command: &str, path: &Path, layout: &SplitLayout) -> Result<(), String> {
+        let bias = layout.size.map(|size| format!("--bias={}", size));
+        let mut options = vec![];
+        if layout.window {
+            options.push("--type=tab");
+        } else {
+            options.push("--type=window");
+            options.push(match layout.direction {
+                SplitDirection::Horizontal => "--location=vsplit",
+                SplitDirection::Vertical => "--location=hsplit",
+            });
+            if let Some(bias) = &bias {
+                options.push(bias);
+            }
+        }
+        if !layout.focus {
+            options.push("--keep-focus");
+        }
+        self.launch(&options, command, path)
     }
 
-    let multiplexer = match detect_multiplexer() {
-        Some(m) => m,
-        None => return false,
+    fn open_window(&self, command: &str, path: &Path) -> Result<(), String> {
+        self.launch(&["--type=tab", "--keep-focus"], command, path)
+    }
+}
+
+/// Run a terminal program (editor or AI assistant) inside `path` in a split
+/// pane laid out by the `[split]` settings. Returns false when not inside a
+/// multiplexer or on failure.
+pub fn try_open_in_split(config: &Config, command: &str, path: &Path) -> bool {
+    let Some(multiplexer) = detect_multiplexer(config) else {
+        return false;
     };
 
-    let result = match multiplexer {
-        Multiplexer::Tmux => open_in_tmux_split(editor, path),
-        Multiplexer::Cmux => open_in_cmux_split(editor, path),
+    if let Err(e) = multiplexer.open_split(command, path, &config.split) {
+        eprintln!(
+            "Warning: failed to open {} in a {} pane: {}",
+            command,
+            multiplexer.name(),
+            e
+        );
+        return false;
+    }
+
+    true
+}
+
+/// Run a command inside `path` in a new multiplexer window, tab or pane.
+/// Returns false when not inside a multiplexer or on failure.
+pub fn try_open_in_window(config: &Config, command: &str, path: &Path) -> bool {
+    let Some(multiplexer) = detect_multiplexer(config) else {
+        return false;
     };
 
-    if let Err(e) = result {
-        eprintln!("Warning: failed to open editor in split pane: {}", e);
+    if let Err(e) = multiplexer.open_window(command, path) {
+        eprintln!(
+            "Warning: failed to open {} in a new {} window: {}",
+            command,
+            multiplexer.name(),
+            e
+        );
         return false;
     }
 
     true
 }
+
+/// tmux session name for a project: its directory name, with the `.` and `:`
+/// characters tmux does not allow in session names replaced
+pub fn session_name(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "root".to_string());
+    name.replace(['.', ':'], "_")
+}
+
+/// Session name for a project whose directory name is taken by a session of
+/// another directory: the name with a short hash of the full path (FNV-1a,
+/// stable across pj builds)
+fn unique_session_name(path: &Path) -> String {
+    let hash = path
+        .to_string_lossy()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+    format!("{}_{:06x}", session_name(path), hash & 0xffffff)
+}
+
+fn tmux_session_exists(name: &str) -> bool {
+    directive::isolate(&mut Command::new("tmux"))
+        .args(["has-session", "-t", &format!("={}", name)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Directory a tmux session was started in, or `None` when there is no such
+/// session
+fn tmux_session_path(name: &str) -> Option<PathBuf> {
+    if !tmux_session_exists(name) {
+        return None;
+    }
+    let target = format!("={}:", name);
+    run("tmux", &["display-message", "-p", "-t", &target, "#{session_path}"])
+        .ok()
+        .map(|out| PathBuf::from(out.trim_end_matches('\n')))
+}
+
+/// Create a detached tmux session rooted at `path` unless it already exists.
+/// Sessions are named after the directory, or after the directory and a hash
+/// of the path when a session of another directory has that name. Each
+/// window is a name and the command it runs (a shell when `None`); the first
+/// window stays selected. Returns the session name.
+pub fn ensure_tmux_session(
+    path: &Path,
+    windows: &[(&str, Option<String>)],
+) -> Result<String, String> {
+    let mut name = session_name(path);
+    match tmux_session_path(&name) {
+        Some(session_path) if session_path == path => return Ok(name),
+        Some(_) => {
+            name = unique_session_name(path);
+            if tmux_session_path(&name).is_some() {
+                return Ok(name);
+            }
+        }
+        None => {}
+    }
+
+    let path_str = path.display().to_string();
+    let target = format!("={}:", name);
+    let shell = [("shell", None)];
+    let windows = if windows.is_empty() { &shell[..] } else { windows };
+
+    for (index, (window, command)) in windows.iter().enumerate() {
+        let mut args = if index == 0 {
+            vec!["new-session", "-d", "-s", &name]
+        } else {
+            vec!["new-window", "-d", "-t", &target]
+        };
+        args.extend(["-n", window, "-c", &path_str]);
+        if let Some(command) = command {
+            args.push(command);
+        }
+
+        run("tmux", &args)?;
+    }
+    Ok(name)
+}
+
+/// Switch the tmux client to a session when pj runs inside tmux. Outside
+/// tmux, returns the attach command for the shell function to run.
+pub fn switch_to_tmux_session(name: &str) -> Result<Option<String>, String> {
+    let target = format!("={}", name);
+    if env::var_os("TMUX").is_none() {
+        return Ok(Some(format!(
+            "tmux attach-session -t {}",
+            shell_words::quote(&target)
+        )));
+    }
+
+    run("tmux", &["switch-client", "-t", &target])?;
+    Ok(None)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::Mutex;
+
+    /// The tests change PATH and the detection variables of the whole process
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    const DETECTION_VARS: &[&str] = &[
+        "CMUX_SOCKET",
+        "CMUX_SOCKET_PATH",
+        "TMUX",
+        "ZELLIJ",
+        "WEZTERM_PANE",
+        "KITTY_WINDOW_ID",
+        "KITTY_LISTEN_ON",
+    ];
+
+    const PROJECT: &str = "/work/acme/api";
+
+    /// Detect the multiplexer with only `vars` set
+    fn detect(vars: &[(&str, &str)]) -> Option<Box<dyn Backend>> {
+        for var in DETECTION_VARS {
+            env::remove_var(var);
+        }
+        for (var, value) in vars {
+            env::set_var(var, value);
+        }
+        detect_multiplexer(&Config::default())
+    }
+
+    /// Put a stub `program` that logs its arguments first in PATH, detect the
+    /// multiplexer from `vars` and run `f` with it. Returns the arguments of
+    /// each call to the stub.
+    fn record(
+        program: &str,
+        vars: &[(&str, &str)],
+        f: impl FnOnce(&dyn Backend) -> Result<(), String>,
+    ) -> Vec<Vec<String>> {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let dir = env::temp_dir().join(format!("pj-test-{}-{}", program, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("calls");
+        let stub = dir.join(program);
+        fs::write(
+            &stub,
+            format!(
+                "#!/bin/sh\nfor arg in \"$@\"; do printf '%s\\n' \"$arg\"; done >> '{log}'\necho '<end>' >> '{log}'\necho 'OK surface:1'\n",
+                log = log.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path = env::var_os("PATH").unwrap_or_default();
+        let paths = std::iter::once(dir.clone()).chain(env::split_paths(&path));
+        env::set_var("PATH", env::join_paths(paths).unwrap());
+
+        let backend = detect(vars).expect("no multiplexer detected");
+        let result = f(backend.as_ref());
+        env::set_var("PATH", path);
+
+        let calls = fs::read_to_string(&log).unwrap_or_default();
+        fs::remove_dir_all(&dir).unwrap();
+        result.unwrap();
+        calls
+            .split_terminator("<end>\n")
+            .map(|call| call.lines().map(String::from).collect())
+            .collect()
+    }
+
+    fn layout(direction: SplitDirection, size: Option<u8>, window: bool) -> SplitLayout {
+        SplitLayout {
+            direction,
+            size,
+            focus: false,
+            window,
+            ..SplitLayout::default()
+        }
+    }
+
+    #[test]
+    fn tmux_split() {
+        let calls = record("tmux", &[("TMUX", "/tmp/tmux-0/default,1,0")], |mux| {
+            let layout = layout(SplitDirection::Horizontal, Some(40), false);
+            mux.open_split("nvim", Path::new(PROJECT), &layout)
+        });
+        assert_eq!(
+            calls,
+            [["split-window", "-h", "-l", "40%", "-d", "-c", PROJECT, "nvim"]]
+        );
+    }
+
+    #[test]
+    fn tmux_window() {
+        let calls = record("tmux", &[("TMUX", "/tmp/tmux-0/default,1,0")], |mux| {
+            mux.open_window("claude", Path::new(PROJECT))
+        });
+        assert_eq!(
+            calls,
+            [["new-window", "-d", "-n", "api", "-c", PROJECT, "claude"]]
+        );
+    }
+
+    #[test]
+    fn cmux_split() {
+        let calls = record("cmux", &[("CMUX_SOCKET_PATH", "/tmp/cmux.sock")], |mux| {
+            let layout = layout(SplitDirection::Vertical, None, false);
+            mux.open_split("nvim 'C:\\notes'", Path::new(PROJECT), &layout)
+        });
+        assert_eq!(
+            calls,
+            [
+                vec!["new-split", "down"],
+                vec!["read-screen", "--surface", "surface:1"],
+                vec![
+                    "send",
+                    "--surface",
+                    "surface:1",
+                    "--",
+                    "cd /work/acme/api && nvim 'C:\\\\notes'\\n"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn cmux_window() {
+        let calls = record("cmux", &[("CMUX_SOCKET", "/tmp/cmux.sock")], |mux| {
+            mux.open_window("claude", Path::new("/work/my api"))
+        });
+        assert_eq!(
+            calls,
+            [
+                vec!["new-split", "right"],
+                vec!["read-screen", "--surface", "surface:1"],
+                vec!["send", "--surface", "surface:1", "--", "cd '/work/my api' && claude\\n"],
+            ]
+        );
+    }
+
+    #[test]
+    fn zellij_split() {
+        let calls = record("zellij", &[("ZELLIJ", "0")], |mux| {
+            let layout = layout(SplitDirection::Vertical, Some(40), false);
+            mux.open_split("nvim", Path::new(PROJECT), &layout)
+        });
+        assert_eq!(
+            calls,
+            [[
+                "action",
+                "new-pane",
+                "--direction",
+                "down",
+                "--cwd",
+                PROJECT,
+                "--name",
+                "api",
+                "--close-on-exit",
+                "--",
+                "sh",
+                "-c",
+                "nvim"
+            ]]
+        );
+    }
+
+    #[test]
+    fn zellij_window() {
+        let calls = record("zellij", &[("ZELLIJ", "0")], |mux| {
+            mux.open_window("claude", Path::new(PROJECT))
+        });
+        assert_eq!(
+            calls,
+            [[
+                "action",
+                "new-pane",
+                "--cwd",
+                PROJECT,
+                "--name",
+                "api",
+                "--close-on-exit",
+                "--",
+                "sh",
+                "-c",
+                "claude"
+            ]]
+        );
+    }
+
+    #[test]
+    fn wezterm_split_refocuses() {
+        let calls = record("wezterm", &[("WEZTERM_PANE", "7")], |mux| {
+            let layout = layout(SplitDirection::Horizontal, Some(30), false);
+            mux.open_split("nvim", Path::new(PROJECT), &layout)
+        });
+        assert_eq!(
+            calls,
+            [
+                vec![
+                    "cli",
+                    "split-pane",
+                    "--right",
+                    "--percent",
+                    "30",
+                    "--cwd",
+                    PROJECT,
+                    "--",
+                    "sh",
+                    "-c",
+                    "nvim"
+                ],
+                vec!["cli", "activate-pane", "--pane-id", "7"],
+            ]
+        );
+    }
+
+    #[test]
+    fn wezterm_window() {
+        let calls = record("wezterm", &[("WEZTERM_PANE", "7")], |mux| {
+            mux.open_window("claude", Path::new(PROJECT))
+        });
+        assert_eq!(
+            calls,
+            [
+                vec!["cli", "spawn", "--cwd", PROJECT, "--", "sh", "-c", "claude"],
+                vec!["cli", "activate-pane", "--pane-id", "7"],
+            ]
+        );
+    }
+
+    #[test]
+    fn kitty_split() {
+        let calls = record("kitty", &[("KITTY_LISTEN_ON", "unix:/tmp/kitty")], |mux| {
+            let layout = layout(SplitDirection::Vertical, Some(40), false);
+            mux.open_split("nvim", Path::new(PROJECT), &layout)
+        });
+        assert_eq!(
+            calls,
+            [[
+                "@",
+                "--to",
+                "unix:/tmp/kitty",
+                "launch",
+                "--cwd=/work/acme/api",
+                "--title=api",
+                "--type=window",
+                "--location=hsplit",
+                "--bias=40",
+                "--keep-focus",
+                "sh",
+                "-c",
+                "nvim"
+            ]]
+        );
+    }
+
+    #[test]
+    fn kitty_window() {
+        let calls = record("kitty", &[("KITTY_LISTEN_ON", "unix:/tmp/kitty")], |mux| {
+            mux.open_window("claude", Path::new(PROJECT))
+        });
+        assert_eq!(
+            calls,
+            [[
+                "@",
+                "--to",
+                "unix:/tmp/kitty",
+                "launch",
+                "--cwd=/work/acme/api",
+                "--title=api",
+                "--type=tab",
+                "--keep-focus",
+                "sh",
+                "-c",
+                "claude"
+            ]]
+        );
+    }
+
+    #[test]
+    fn detection() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let name = |vars: &[(&str, &str)]| detect(vars).map(|mux| mux.name());
+
+        assert_eq!(name(&[]), None);
+        assert_eq!(name(&[("KITTY_WINDOW_ID", "1")]), None);
+        assert_eq!(name(&[("KITTY_LISTEN_ON", "unix:/tmp/kitty")]), Some("kitty"));
+        assert_eq!(name(&[("ZELLIJ", "0"), ("TMUX", "/tmp/tmux")]), Some("tmux"));
+        assert_eq!(name(&[("TMUX", "/tmp/tmux"), ("CMUX_SOCKET", "/tmp/cmux")]), Some("cmux"));
+        assert_eq!(name(&[("WEZTERM_PANE", "1"), ("ZELLIJ", "0")]), Some("Zellij"));
+    }
+}
@@ -0,0 +1,231 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::config::Config;
+use crate::tui::PickerAction;
+
+/// Picker configured with `picker` in config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PickerKind {
+    /// skim when attached to a terminal, the plain list otherwise
+    #[default]
+    Auto,
+    Skim,
+    Fzf,
+    Plain,
+}
+
+/// A picker line: the item's path, group name for group entries, and what
+/// is shown
+pub struct Line<'a> {
+    pub path: &'a str,
+    pub group: Option<&'a str>,
+    pub display: &'a str,
+}
+
+/// Indices of the chosen lines and the action key pressed, if any
+pub struct Picked {
+    pub indices: Vec<usize>,
+    pub action: Option<PickerAction>,
+}
+
+/// Resolve `auto` and fall back when the configured picker cannot run
+pub fn resolve(kind: PickerKind) -> PickerKind {
+    let interactive = io::stdin().is_terminal() && io::stderr().is_terminal();
+    match kind {
+        PickerKind::Fzf if find_in_path("fzf").is_some() => PickerKind::Fzf,
+        PickerKind::Fzf => {
+            eprintln!("Warning: fzf not found in PATH, using the built-in picker");
+            resolve(PickerKind::Auto)
+        }
+        PickerKind::Skim | PickerKind::Auto if interactive => PickerKind::Skim,
+        _ => PickerKind::Plain,
+    }
+}
+
+/// Full path of an executable found in PATH
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Run fzf over the lines. Items are fed as `index<TAB>path<TAB>group<TAB>display`
+/// and only the display is shown; the preview is rendered by
+/// `pj --preview-path` (with `--preview-group` for group entries).
+/// fzf applies `--nth` to the shown fields, so it matches the display rather
+/// than the items' searchable text.
+pub fn fzf(
+    lines: &[Line],
+    query: Option<&str>,
+    config: &Config,
+    multi: bool,
+    actions: bool,
+    header: &str,
+) -> Result<Option<Picked>> {
+    let mut args = vec![
+        "--ansi".to_string(),
+        "--delimiter=\t".to_string(),
+        "--with-nth=4..".to_string(),
+        "--layout=reverse".to_string(),
+    ];
+    if multi {
+        args.push("--multi".to_string());
+    }
+    if let Some(q) = query {
+        args.push(format!("--query={}", q));
+    }
+    if !header.is_empty() {
+        args.push(format!("--header={}", header));
+    }
+
+    let keys: Vec<(&str, PickerAction)> = PickerAction::ALL
+        .into_iter()
+        .map(|action| (action.key(&config.keys), action))
+        .filter(|(key, _)| actions && !key.is_empty())
+        .collect();
+    if !keys.is_empty() {
+        let expect: Vec<&str> = keys.iter().map(|(key, _)| *key).collect();
+        args.push(format!("--expect={}", expect.join(",")));
+    }
+
+    if let (true, Ok(exe)) = (actions, env::current_exe()) {
+        let preview_window = if config.preview {
+            config.preview_window.clone()
+        } else {
+            format!("{}:hidden", config.preview_window)
+        };
+        args.push(format!(
+            "--preview={} --preview-path {{2}} --preview-group {{3}}",
+            shell_quote(&exe.display().to_string())
+        ));
+        args.push(format!("--preview-window={}", preview_window));
+        args.push(format!("--bind={}:toggle-preview", config.preview_toggle_key));
+    }
+
+    let mut child = Command::new("fzf")
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Failed to run fzf")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        for (index, line) in lines.iter().enumerate() {
+            // fzf may exit before reading everything; that is not an error
+            let group = line.group.unwrap_or_default();
+            if writeln!(stdin, "{}\t{}\t{}\t{}", index, line.path, group, line.display).is_err() {
+                break;
+            }
+        }
+    }
+
+    let output = child.wait_with_output().context("Failed to run fzf")?;
+    // 1: no match, 130: aborted
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut out_lines = stdout.lines();
+    let action = if keys.is_empty() {
+        None
+    } else {
+        let pressed = out_lines.next().unwrap_or("");
+        keys.iter()
+            .find(|(key, _)| *key == pressed)
+            .map(|(_, action)| *action)
+    };
+    let indices: Vec<usize> = out_lines
+        .filter_map(|line| line.split('\t').next()?.parse().ok())
+        .collect();
+
+    if indices.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Picked { indices, action }))
+}
+
+/// Numbered list on stderr, answered on stdin. With `actions`, an action name
+/// may precede the number(s), e.g. `tags 3`.
+pub fn plain(lines: &[Line], multi: bool, actions: bool) -> Result<Option<Picked>> {
+    let width = lines.len().to_string().len();
+    for (index, line) in lines.iter().enumerate() {
+        eprintln!("{:>width$}) {}", index + 1, strip_ansi(line.display), width = width);
+    }
+
+    let what = if multi {
+        "Select projects (numbers separated by spaces"
+    } else {
+        "Select a project (number"
+    };
+    let action_hint = if actions {
+        let names: Vec<&str> = PickerAction::ALL.iter().map(|a| a.name()).collect();
+        format!(", optionally after one of {}", names.join("/"))
+    } else {
+        String::new()
+    };
+    eprint!("{}{}; empty to cancel): ", what, action_hint);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer)? == 0 {
+        return Ok(None);
+    }
+
+    let mut words = answer.split_whitespace().peekable();
+    let action = match words.peek() {
+        Some(word) if actions => {
+            let action = PickerAction::from_name(word);
+            if action.is_some() {
+                words.next();
+            }
+            action
+        }
+        _ => None,
+    };
+
+    let mut indices = Vec::new();
+    for word in words {
+        match word.parse::<usize>() {
+            Ok(n) if (1..=lines.len()).contains(&n) => indices.push(n - 1),
+            _ => bail!("Invalid selection: {}", word),
+        }
+    }
+    if indices.is_empty() {
+        return Ok(None);
+    }
+    if !multi && indices.len() > 1 {
+        bail!("Select a single project");
+    }
+    Ok(Some(Picked { indices, action }))
+}
+
+/// Remove ANSI escape sequences, for output that may not be a terminal
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip `ESC [ ... <letter>`
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use std::fs;
+use std::path::Path;
+
+use crate::git::{current_branch, is_git_repo, recent_commits, status_lines};
+use crate::projects::{Group, Project, ProjectStore};
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+const COMMIT_COUNT: usize = 5;
+const README_LINES: usize = 20;
+const README_NAMES: &[&str] = &["README.md", "README", "README.rst", "README.txt", "readme.md"];
+
+/// Print the preview of a project, or of the named group, for pickers that
+/// render previews with an external command (fzf)
+pub fn run(path: &Path, group: Option<&str>) -> Result<()> {
+    let store = ProjectStore::load()?;
+    if let Some(group) = group.and_then(|name| store.groups().iter().find(|g| g.name == name)) {
+        print!("{}", render_group(group));
+        return Ok(());
+    }
+    let project = store
+        .find_by_path(path)
+        .cloned()
+        .unwrap_or_else(|| Project::new(path.to_path_buf()));
+    print!("{}", render(&project));
+    Ok(())
+}
+
+/// Render the picker preview for a project: git branch and status, recent
+/// commits, tags and frecency stats, and the head of the README
+pub fn render(project: &Project) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("{}{}{}\n", BOLD, project.path.display(), RESET));
+
+    if !project.exists() {
+        out.push_str(&format!("{}Path does not exist{}\n", YELLOW, RESET));
+        return out;
+    }
+
+    if is_git_repo(&project.path) {
+        render_git(&mut out, &project.path);
+    }
+
+    out.push('\n');
+    let tags = project.tags_display();
+    if !tags.is_empty() {
+        out.push_str(&format!("Tags:     {}\n", tags));
+    }
+    let last_accessed = DateTime::<Utc>::from_timestamp(project.last_accessed, 0)
+        .map(|dt| dt.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "Unknown".to_string());
+    out.push_str(&format!(
+        "Opened:   {} times, last {}\nFrecency: {:.2}\n",
+        project.access_count,
+        last_accessed,
+        project.frecency()
+    ));
+
+    if let Some(readme) = readme_head(&project.path) {
+        out.push_str(&format!("\n{}{}{}\n", DIM, "─".repeat(40), RESET));
+        out.push_str(&readme);
+    }
+
+    out
+}
+
+/// Render the picker preview for a group: its cd target and members with
+/// their branches
+pub fn render_group(group: &Group) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("{}{}{}\n", BOLD, group.name, RESET));
+    out.push_str(&format!("cd:       {}\n\nProjects:\n", group.cd_target().display()));
+    for path in &group.projects {
+        let detail = if !path.exists() {
+            format!(" {}(missing){}", YELLOW, RESET)
+        } else if is_git_repo(path) {
+            current_branch(path)
+                .map(|b| format!(" {}({}){}", DIM, b, RESET))
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+        out.push_str(&format!("  {}{}\n", path.display(), detail));
+    }
+    out
+}
+
+fn render_git(out: &mut String, path: &Path) {
+    let branch = current_branch(path).unwrap_or_else(|| "unknown".to_string());
+    let status = match status_lines(path) {
+        Ok(lines) if lines.is_empty() => format!("{}clean{}", GREEN, RESET),
+        Ok(lines) => format!("{}{} changed{}", YELLOW, lines.len(), RESET),
+        Err(_) => "unknown".to_string(),
+    };
+    out.push_str(&format!("Branch:   {} ({})\n", branch, status));
+
+    let commits = recent_commits(path, COMMIT_COUNT);
+    if !commits.is_empty() {
+        out.push_str("\nRecent commits:\n");
+        for commit in commits {
+            out.push_str(&format!("  {}\n", commit));
+        }
+    }
+}
+
+fn readme_head(path: &Path) -> Option<String> {
+    let content = README_NAMES
+        .iter()
+        .find_map(|name| fs::read_to_string(path.join(name)).ok())?;
+    let head: Vec<&str> = content.lines().take(README_LINES).collect();
+    Some(head.join("\n"))
+}
@@ -1,17 +1,61 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::config::Config;
+use crate::detect::detect_tags;
 use crate::frecency::calculate_frecency;
+use crate::git::{list_worktrees, main_worktree};
+use crate::workspace::members as workspace_members;
+
+/// Normalize a tag: lowercase, and trim whitespace around the `=` of a
+/// `key=value` tag
+pub fn normalize_tag(tag: &str) -> String {
+    let tag = tag.trim().to_lowercase();
+    match tag.split_once('=') {
+        Some((key, value)) => format!("{}={}", key.trim(), value.trim()),
+        None => tag,
+    }
+}
+
+/// Split a `key=value` tag into its key and value
+pub fn split_key_value(tag: &str) -> Option<(&str, &str)> {
+    tag.split_once('=')
+        .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Project {
     pub path: PathBuf,
     pub tags: Vec<String>,
+    /// Tags detected from project content, kept apart from manual tags so
+    /// they can be refreshed without touching what the user set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_tags: Vec<String>,
     pub last_accessed: i64,
     pub access_count: u32,
+    /// Tracked project this entry was discovered from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<PathBuf>,
+    /// Checked-out branch when this entry is a git worktree of its parent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Pinned projects are listed before all others
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub pinned: bool,
+    /// Start the AI assistant in its own multiplexer pane (overrides the
+    /// `ai_pane` setting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_pane: Option<bool>,
+    /// Parent's tags, resolved when the store is loaded
+    #[serde(skip)]
+    pub inherited_tags: Vec<String>,
 }
 
 impl Project {
@@ -19,13 +63,51 @@ impl Project {
         Self {
             path,
             tags: Vec::new(),
+            auto_tags: Vec::new(),
             last_accessed: chrono::Utc::now().timestamp(),
             access_count: 0,
+            parent: None,
+            branch: None,
+            pinned: false,
+            ai_pane: None,
+            inherited_tags: Vec::new(),
         }
     }
 
+    pub fn worktree_of(parent: &Project, path: PathBuf, branch: String) -> Self {
+        let mut project = Self::new(path);
+        project.parent = Some(parent.path.clone());
+        project.branch = Some(branch);
+        project.inherited_tags = parent.tags.clone();
+        project
+    }
+
+    pub fn member_of(parent: &Project, path: PathBuf) -> Self {
+        let mut project = Self::new(path);
+        project.parent = Some(parent.path.clone());
+        project.inherited_tags = parent.tags.clone();
+        project
+    }
+
+    /// Workspace member (sub-project) discovered from the parent's manifests
+    pub fn is_member(&self) -> bool {
+        self.parent.is_some() && self.branch.is_none()
+    }
+
+    pub fn is_worktree(&self) -> bool {
+        self.parent.is_some() && self.branch.is_some()
+    }
+
     pub fn with_tags(mut self, tags: Vec<String>) -> Self {
-        self.tags = tags.into_iter().map(|t| t.to_lowercase()).collect();
+        self.tags = tags.iter().map(|t| normalize_tag(t)).collect();
+        self
+    }
+
+    /// Add the tags `pj -a` gives a project: tags from matching tag rules
+    /// and detected auto tags. Used for entries discovered by scans.
+    pub fn with_scan_tags(mut self, config: &Config) -> Self {
+        self.add_tags(config.rule_tags(&self.path));
+        self.refresh_auto_tags();
         self
     }
 
@@ -36,16 +118,103 @@ impl Project {
 
     pub fn add_tags(&mut self, tags: Vec<String>) {
         for tag in tags {
-            let tag_lower = tag.to_lowercase();
-            if !self.tags.contains(&tag_lower) {
-                self.tags.push(tag_lower);
+            let tag = normalize_tag(&tag);
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
             }
         }
     }
 
     pub fn remove_tags(&mut self, tags: &[String]) {
-        let tags_lower: Vec<String> = tags.iter().map(|t| t.to_lowercase()).collect();
-        self.tags.retain(|t| !tags_lower.contains(t));
+        let tags_normalized: Vec<String> = tags.iter().map(|t| normalize_tag(t)).collect();
+        self.tags.retain(|t| !tags_normalized.contains(t));
+    }
+
+    /// Re-detect auto tags from the project's files. Missing projects keep
+    /// their previous auto tags. Returns true if the auto tags changed.
+    pub fn refresh_auto_tags(&mut self) -> bool {
+        if !self.exists() {
+            return false;
+        }
+        let detected = detect_tags(&self.path);
+        let changed = detected != self.auto_tags;
+        self.auto_tags = detected;
+        changed
+    }
+
+    /// Manual tags followed by inherited and auto tags not already present
+    pub fn effective_tags(&self) -> Vec<&str> {
+        let mut tags: Vec<&str> = self.tags.iter().map(|t| t.as_str()).collect();
+        for tag in self.inherited_tags.iter().chain(&self.auto_tags) {
+            if !tags.contains(&tag.as_str()) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    /// Tags for display: manual and inherited tags, then auto tags in
+    /// parentheses
+    pub fn tags_display(&self) -> String {
+        let mut own: Vec<&str> = self.tags.iter().map(|t| t.as_str()).collect();
+        for tag in &self.inherited_tags {
+            if !own.contains(&tag.as_str()) {
+                own.push(tag);
+            }
+        }
+        let auto: Vec<&str> = self
+            .auto_tags
+            .iter()
+            .map(|t| t.as_str())
+            .filter(|t| !own.contains(t))
+            .collect();
+        match (own.is_empty(), auto.is_empty()) {
+            (_, true) => own.join(", "),
+            (true, false) => format!("({})", auto.join(", ")),
+            (false, false) => format!("{} ({})", own.join(", "), auto.join(", ")),
+        }
+    }
+
+    /// Values of the `key=value` tags with the given key
+    pub fn tag_values(&self, key: &str) -> Vec<&str> {
+        self.effective_tags()
+            .into_iter()
+            .filter_map(split_key_value)
+            .filter(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    /// Text matched by filters and the picker: path, branch and tags, with
+    /// `key=value` tags also searchable as `key:value`
+    pub fn searchable_text(&self) -> String {
+        let mut text = self.path.display().to_string();
+        if let Some(branch) = &self.branch {
+            text.push(' ');
+            text.push_str(branch);
+        }
+        for tag in self.effective_tags() {
+            text.push(' ');
+            text.push_str(tag);
+            if let Some((key, value)) = split_key_value(tag) {
+                text.push_str(&format!(" {}:{}", key, value));
+            }
+        }
+        text
+    }
+
+    /// Replace every tag in `from` with `to`, or drop them when `to` is `None`.
+    /// Returns true if the project carried any of the tags.
+    pub fn replace_tags(&mut self, from: &[String], to: Option<&str>) -> bool {
+        let had_any = self.tags.iter().any(|t| from.contains(t));
+        if !had_any {
+            return false;
+        }
+        self.tags.retain(|t| !from.contains(t));
+        if let Some(to) = to {
+            self.add_tags(vec![to.to_string()]);
+        }
+        true
     }
 
     pub fn exists(&self) -> bool {
@@ -57,9 +226,46 @@ impl Project {
     }
 }
 
+/// Named set of projects opened together
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Group {
+    pub name: String,
+    pub projects: Vec<PathBuf>,
+    /// Directory the shell changes into; the members' common parent if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cd: Option<PathBuf>,
+}
+
+impl Group {
+    pub fn cd_target(&self) -> PathBuf {
+        if let Some(cd) = &self.cd {
+            return cd.clone();
+        }
+        let mut members = self.projects.iter();
+        let Some(first) = members.next() else {
+            return PathBuf::new();
+        };
+        let mut common = first.clone();
+        for member in members {
+            while !member.starts_with(&common) {
+                if !common.pop() {
+                    break;
+                }
+            }
+        }
+        common
+    }
+
+    pub fn searchable_text(&self) -> String {
+        let members: Vec<String> = self.projects.iter().map(|p| p.display().to_string()).collect();
+        format!("{} {}", self.name, members.join(" "))
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ProjectStore {
     projects: Vec<Project>,
+    groups: Vec<Group>,
 }
 
 impl ProjectStore {
@@ -72,7 +278,37 @@ impl ProjectStore {
             .with_context(|| format!("Failed to read projects file: {}", path.display()))?;
         let projects: Vec<Project> =
             serde_json::from_str(&content).with_context(|| "Failed to parse projects file")?;
-        Ok(Self { projects })
+        let groups = Self::load_groups()?;
+        let mut store = Self { projects, groups };
+        store.resolve_inherited_tags();
+        Ok(store)
+    }
+
+    fn load_groups() -> Result<Vec<Group>> {
+        let path = Config::groups_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read groups file: {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| "Failed to parse groups file")
+    }
+
+    fn resolve_inherited_tags(&mut self) {
+        let parent_tags: Vec<Vec<String>> = self
+            .projects
+            .iter()
+            .map(|p| {
+                p.parent
+                    .as_ref()
+                    .and_then(|parent| self.find_by_path(parent))
+                    .map(|parent| parent.tags.clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+        for (project, tags) in self.projects.iter_mut().zip(parent_tags) {
+            project.inherited_tags = tags;
+        }
     }
 
     pub fn save(&self) -> Result<()> {
@@ -86,9 +322,69 @@ impl ProjectStore {
             serde_json::to_string_pretty(&self.projects).context("Failed to serialize projects")?;
         fs::write(&path, content)
             .with_context(|| format!("Failed to write projects file: {}", path.display()))?;
+
+        let groups_path = Config::groups_path()?;
+        if !self.groups.is_empty() || groups_path.exists() {
+            let content =
+                serde_json::to_string_pretty(&self.groups).context("Failed to serialize groups")?;
+            fs::write(&groups_path, content).with_context(|| {
+                format!("Failed to write groups file: {}", groups_path.display())
+            })?;
+        }
         Ok(())
     }
 
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    pub fn find_group(&self, name: &str) -> Option<&Group> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    /// Add projects to a group, creating it if needed. Returns how many
+    /// projects were new to the group.
+    pub fn add_to_group(&mut self, name: &str, paths: &[PathBuf]) -> usize {
+        let index = match self.groups.iter().position(|g| g.name == name) {
+            Some(index) => index,
+            None => {
+                self.groups.push(Group {
+                    name: name.to_string(),
+                    projects: Vec::new(),
+                    cd: None,
+                });
+                self.groups.len() - 1
+            }
+        };
+        let group = &mut self.groups[index];
+        let mut added = 0;
+        for path in paths {
+            if !group.projects.contains(path) {
+                group.projects.push(path.clone());
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Set the directory the shell changes into when the group is opened.
+    /// Returns false if there is no such group.
+    pub fn set_group_cd(&mut self, name: &str, cd: PathBuf) -> bool {
+        match self.groups.iter_mut().find(|g| g.name == name) {
+            Some(group) => {
+                group.cd = Some(cd);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove_group(&mut self, name: &str) -> bool {
+        let len_before = self.groups.len();
+        self.groups.retain(|g| g.name != name);
+        self.groups.len() < len_before
+    }
+
     pub fn add(&mut self, project: Project) -> bool {
         if let Some(existing) = self.find_by_path_mut(&project.path) {
             existing.last_accessed = chrono::Utc::now().timestamp();
@@ -108,22 +404,177 @@ impl ProjectStore {
         self.projects.iter_mut().find(|p| p.path == path)
     }
 
+    /// Remove a project along with the entries discovered from it
     pub fn remove(&mut self, path: &Path) -> bool {
         let len_before = self.projects.len();
-        self.projects.retain(|p| p.path != path);
+        self.projects
+            .retain(|p| p.path != path && p.parent.as_deref() != Some(path));
+        self.prune_groups();
         self.projects.len() < len_before
     }
 
+    /// Drop group members that are no longer tracked, and emptied groups
+    fn prune_groups(&mut self) {
+        let projects = &self.projects;
+        for group in &mut self.groups {
+            group
+                .projects
+                .retain(|member| projects.iter().any(|p| &p.path == member));
+        }
+        self.groups.retain(|g| !g.projects.is_empty());
+    }
+
+    /// The tracked entry (project, worktree or member) closest to `path`
+    /// among those containing it
+    pub fn find_entry_containing(&self, path: &Path) -> Option<&Project> {
+        self.projects
+            .iter()
+            .filter(|p| path.starts_with(&p.path))
+            .max_by_key(|p| p.path.components().count())
+    }
+
+    /// The top-level project containing `path`, preferring the deepest match
+    pub fn find_containing(&self, path: &Path) -> Option<&Project> {
+        let project = self.find_entry_containing(path)?;
+        match &project.parent {
+            Some(parent) => self.find_by_path(parent),
+            None => Some(project),
+        }
+    }
+
+    pub fn worktrees_of(&self, path: &Path) -> Vec<&Project> {
+        self.projects
+            .iter()
+            .filter(|p| p.is_worktree() && p.parent.as_deref() == Some(path))
+            .collect()
+    }
+
+    /// Track the linked worktrees of a git project and forget the ones that
+    /// no longer exist. Entries already tracked by hand are moved under the
+    /// project. Does nothing when `path` is itself a linked worktree. Returns
+    /// the number of worktrees added and removed.
+    pub fn sync_worktrees(&mut self, config: &Config, path: &Path) -> Result<(usize, usize)> {
+        if main_worktree(path)? != path {
+            return Ok((0, 0));
+        }
+        let worktrees = list_worktrees(path)?;
+        let parent = self
+            .find_by_path(path)
+            .cloned()
+            .with_context(|| format!("Project not found: {}", path.display()))?;
+
+        let mut added = 0;
+        for worktree in &worktrees {
+            match self.find_by_path_mut(&worktree.path) {
+                Some(existing) => {
+                    if existing.parent.as_ref() != Some(&parent.path) {
+                        existing.parent = Some(parent.path.clone());
+                        added += 1;
+                    }
+                    existing.branch = Some(worktree.branch.clone());
+                    existing.inherited_tags = parent.tags.clone();
+                }
+                None => {
+                    let project = Project::worktree_of(
+                        &parent,
+                        worktree.path.clone(),
+                        worktree.branch.clone(),
+                    );
+                    self.projects.push(project.with_scan_tags(config));
+                    added += 1;
+                }
+            }
+        }
+
+        let len_before = self.projects.len();
+        self.projects.retain(|p| {
+            !(p.is_worktree()
+                && p.parent.as_deref() == Some(path)
+                && !worktrees.iter().any(|w| w.path == p.path))
+        });
+        let removed = len_before - self.projects.len();
+
+        Ok((added, removed))
+    }
+
     pub fn remove_missing(&mut self) -> usize {
         let len_before = self.projects.len();
         self.projects.retain(|p| p.path.exists());
+        self.prune_groups();
         len_before - self.projects.len()
     }
 
+    /// Track the workspace members declared in a project's manifests and
+    /// forget the ones no longer declared. Members already tracked by hand
+    /// are moved under the project. Returns the number of members added and
+    /// removed.
+    pub fn sync_members(&mut self, config: &Config, path: &Path) -> Result<(usize, usize)> {
+        let members = workspace_members(path);
+        let parent = self
+            .find_by_path(path)
+            .cloned()
+            .with_context(|| format!("Project not found: {}", path.display()))?;
+
+        let mut added = 0;
+        for member in members.iter().filter(|member| *member != path) {
+            match self.find_by_path_mut(member) {
+                // Worktrees stay under their repository
+                Some(existing) if existing.branch.is_some() => {}
+                Some(existing) => {
+                    if existing.parent.as_ref() != Some(&parent.path) {
+                        existing.parent = Some(parent.path.clone());
+                        added += 1;
+                    }
+                    existing.inherited_tags = parent.tags.clone();
+                }
+                None => {
+                    let project = Project::member_of(&parent, member.clone());
+                    self.projects.push(project.with_scan_tags(config));
+                    added += 1;
+                }
+            }
+        }
+
+        let len_before = self.projects.len();
+        self.projects.retain(|p| {
+            !(p.is_member() && p.parent.as_deref() == Some(path) && !members.contains(&p.path))
+        });
+        let removed = len_before - self.projects.len();
+
+        Ok((added, removed))
+    }
+
+    /// Projects ranked by frecency with pinned projects first, and worktrees
+    /// listed right after their parent project
     pub fn sorted_by_frecency(&self) -> Vec<&Project> {
-        let mut projects: Vec<&Project> = self.projects.iter().collect();
-        projects.sort_by(|a, b| b.frecency().partial_cmp(&a.frecency()).unwrap());
-        projects
+        let by_frecency = |a: &&Project, b: &&Project| {
+            b.pinned
+                .cmp(&a.pinned)
+                .then(b.frecency().partial_cmp(&a.frecency()).unwrap())
+        };
+
+        let mut projects: Vec<&Project> = self
+            .projects
+            .iter()
+            .filter(|p| !p.is_worktree() || !self.has_parent(p))
+            .collect();
+        projects.sort_by(by_frecency);
+
+        let mut sorted = Vec::with_capacity(self.projects.len());
+        for project in projects {
+            sorted.push(project);
+            let mut worktrees = self.worktrees_of(&project.path);
+            worktrees.sort_by(by_frecency);
+            sorted.extend(worktrees);
+        }
+        sorted
+    }
+
+    fn has_parent(&self, project: &Project) -> bool {
+        project
+            .parent
+            .as_ref()
+            .is_some_and(|parent| self.find_by_path(parent).is_some())
     }
 
     pub fn all_tags(&self) -> Vec<String> {
@@ -136,4 +587,68 @@ impl ProjectStore {
         tags.dedup();
         tags
     }
+
+    /// Re-detect auto tags on every project. Returns the number changed.
+    pub fn refresh_auto_tags(&mut self) -> usize {
+        self.projects
+            .iter_mut()
+            .map(|p| p.refresh_auto_tags())
+            .filter(|changed| *changed)
+            .count()
+    }
+
+    /// Tags from the config's tag rules that each project is missing
+    pub fn missing_rule_tags(&self, config: &Config) -> Vec<(PathBuf, Vec<String>)> {
+        self.projects
+            .iter()
+            .filter_map(|p| {
+                let missing: Vec<String> = config
+                    .rule_tags(&p.path)
+                    .into_iter()
+                    .map(|t| normalize_tag(&t))
+                    .filter(|t| !p.tags.contains(t))
+                    .collect();
+                (!missing.is_empty()).then(|| (p.path.clone(), missing))
+            })
+            .collect()
+    }
+
+    /// Known values of every `key=value` tag, keyed by tag key
+    pub fn tag_values_by_key(&self) -> BTreeMap<String, Vec<String>> {
+        let mut keys: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for tag in self.all_tags() {
+            if let Some((key, value)) = split_key_value(&tag) {
+                keys.entry(key.to_string()).or_default().push(value.to_string());
+            }
+        }
+        keys
+    }
+
+    /// All tags with the number of projects carrying each, sorted by name
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for tag in self.all_tags() {
+            let count = self.projects.iter().filter(|p| p.tags.contains(&tag)).count();
+            counts.push((tag, count));
+        }
+        counts
+    }
+
+    /// Projects carrying at least one of the given tags
+    pub fn projects_with_any_tag(&self, tags: &[String]) -> Vec<&Project> {
+        self.projects
+            .iter()
+            .filter(|p| p.tags.iter().any(|t| tags.contains(t)))
+            .collect()
+    }
+
+    /// Replace `from` tags with `to` across all projects (or delete them when
+    /// `to` is `None`). Returns the number of projects changed.
+    pub fn replace_tags(&mut self, from: &[String], to: Option<&str>) -> usize {
+        self.projects
+            .iter_mut()
+            .map(|p| p.replace_tags(from, to))
+            .filter(|changed| *changed)
+            .count()
+    }
 }
@@ -47,37 +47,38 @@ impl Shell {
         })
     }
 
+    /// Shell function speaking directive protocol 1 (`directive::PROTOCOL_VERSION`):
+    /// pj writes directives to a temporary file, which the function applies
+    /// in order once pj exits successfully
     pub fn function_code(&self) -> &'static str {
         match self {
             Shell::Bash | Shell::Zsh => {
                 r#"
 # pj - Project Launcher shell integration
 pj() {
-    case "$1" in
-        -a|--add|-i|--init|--config|--list|-h|--help|-V|--version)
-            command pj "$@"
-            return $?
-            ;;
-    esac
-    local output
-    output=$(command pj "$@")
-    local exit_code=$?
-    local dir="" ai_cmd=""
-    while IFS= read -r line; do
-        if [[ "$line" == __PJ_AI__:* ]]; then
-            ai_cmd="${line#__PJ_AI__:}"
-        else
-            dir="$line"
-        fi
-    done <<< "$output"
-    if [[ $exit_code -eq 0 && -n "$dir" && -d "$dir" ]]; then
-        builtin cd "$dir"
-    elif [[ $exit_code -ne 130 && -n "$dir" ]]; then
-        echo "$dir"
-    fi
-    if [[ $exit_code -eq 0 && -n "$ai_cmd" ]]; then
-        eval "$ai_cmd"
+    local directives exit_code line
+    directives=$(mktemp "${TMPDIR:-/tmp}/pj.XXXXXX") || return 1
+    PJ_SHELL_PROTOCOL=1 PJ_DIRECTIVES_FILE="$directives" command pj "$@"
+    exit_code=$?
+    if [[ $exit_code -eq 0 && -s "$directives" ]]; then
+        {
+            IFS= read -r line <&3
+            if [[ "$line" != "pj-directives 1" ]]; then
+                echo "pj: unsupported directives ($line); update the shell function with: pj --init" >&2
+            else
+                while IFS= read -r line <&3; do
+                    case "$line" in
+                        "cd "*) builtin cd -- "${line#cd }" ;;
+                        "eval "*) eval "${line#eval }" 3<&- ;;
+                        "export "*) export -- "${line#export }" ;;
+                        "echo "*) printf '%s\n' "${line#echo }" ;;
+                    esac
+                done
+            fi
+        } 3< "$directives"
     fi
+    command rm -f -- "$directives"
+    return $exit_code
 }
 "#
             }
@@ -85,33 +86,28 @@ pj() {
                 r#"
 # pj - Project Launcher shell integration
 pj() {
-    case "$1" in
-        -a|--add|-i|--init|--config|--list|-h|--help|-V|--version)
-            command pj "$@"
-            return $?
-            ;;
-    esac
-    output=$(command pj "$@")
-    exit_code=$?
-    dir="" ai_cmd=""
-    _pj_ifs="$IFS"
-    IFS='
-'
-    for line in $output; do
-        case "$line" in
-            __PJ_AI__:*) ai_cmd="${line#__PJ_AI__:}" ;;
-            *) dir="$line" ;;
-        esac
-    done
-    IFS="$_pj_ifs"
-    if [ $exit_code -eq 0 ] && [ -n "$dir" ] && [ -d "$dir" ]; then
-        cd "$dir"
-    elif [ $exit_code -ne 130 ] && [ -n "$dir" ]; then
-        echo "$dir"
-    fi
-    if [ $exit_code -eq 0 ] && [ -n "$ai_cmd" ]; then
-        eval "$ai_cmd"
+    _pj_directives=$(mktemp "${TMPDIR:-/tmp}/pj.XXXXXX") || return 1
+    PJ_SHELL_PROTOCOL=1 PJ_DIRECTIVES_FILE="$_pj_directives" command pj "$@"
+    _pj_status=$?
+    if [ $_pj_status -eq 0 ] && [ -s "$_pj_directives" ]; then
+        {
+            IFS= read -r _pj_line <&3
+            if [ "$_pj_line" != "pj-directives 1" ]; then
+                echo "pj: unsupported directives ($_pj_line); update the shell function with: pj --init" >&2
+            else
+                while IFS= read -r _pj_line <&3; do
+                    case "$_pj_line" in
+                        "cd "*) cd -- "${_pj_line#cd }" ;;
+                        "eval "*) eval "${_pj_line#eval }" 3<&- ;;
+                        "export "*) export "${_pj_line#export }" ;;
+                        "echo "*) printf '%s\n' "${_pj_line#echo }" ;;
+                    esac
+                done
+            fi
+        } 3< "$_pj_directives"
     fi
+    command rm -f -- "$_pj_directives"
+    return $_pj_status
 }
 "#
             }
@@ -119,30 +115,34 @@ pj() {
                 r#"
 # pj - Project Launcher shell integration
 function pj
-    switch $argv[1]
-        case -a --add -i --init --config --list -h --help -V --version
-            command pj $argv
-            return $status
-    end
-    set -l output (command pj $argv)
+    set -l tmpdir /tmp
+    set -q TMPDIR; and set tmpdir $TMPDIR
+    set -l directives (mktemp "$tmpdir/pj.XXXXXX"); or return 1
+    env PJ_SHELL_PROTOCOL=1 PJ_DIRECTIVES_FILE=$directives pj $argv
     set -l exit_code $status
-    set -l dir ""
-    set -l ai_cmd ""
-    for line in $output
-        if string match -q "__PJ_AI__:*" $line
-            set ai_cmd (string replace "__PJ_AI__:" "" $line)
+    if test $exit_code -eq 0 -a -s $directives
+        set -l lines (cat $directives)
+        if test "$lines[1]" != "pj-directives 1"
+            echo "pj: unsupported directives ($lines[1]); update the shell function with: pj --init" >&2
         else
-            set dir $line
+            set -e lines[1]
+            for line in $lines
+                switch $line
+                    case 'cd *'
+                        cd (string sub -s 4 -- $line)
+                    case 'eval *'
+                        eval (string sub -s 6 -- $line)
+                    case 'export *'
+                        set -l pair (string split -m 1 = -- (string sub -s 8 -- $line))
+                        set -gx $pair[1] $pair[2]
+                    case 'echo *'
+                        printf '%s\n' (string sub -s 6 -- $line)
+                end
+            end
         end
     end
-    if test $exit_code -eq 0 -a -n "$dir" -a -d "$dir"
-        cd $dir
-    else if test $exit_code -ne 130 -a -n "$dir"
-        echo $dir
-    end
-    if test $exit_code -eq 0 -a -n "$ai_cmd"
-        eval $ai_cmd
-    end
+    command rm -f -- $directives
+    return $exit_code
 end
 "#
             }
@@ -170,10 +170,11 @@ end
         if rc_file.exists() {
             let content = fs::read_to_string(&rc_file)?;
             if let Some(start) = content.find(marker) {
-                // Find the end of the existing function
+                // The existing function ends at the first unindented closing
+                // line; later functions in the rc file are left alone
                 let after_marker = &content[start..];
-                let end_marker = self.end_marker();
-                if let Some(end_offset) = after_marker.rfind(end_marker) {
+                let end_marker = format!("\n{}", self.end_marker());
+                if let Some(end_offset) = after_marker.find(&end_marker) {
                     let end = start + end_offset + end_marker.len();
                     let mut new_content = String::new();
                     new_content.push_str(&content[..start]);
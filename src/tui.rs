@@ -1,15 +1,23 @@
 use anyhow::Result;
 use skim::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use crate::projects::Project;
+use crate::config::{Config, KeyBindings};
+use crate::git::{self, RepoStatus};
+use crate::picker::{self, Line, Picked, PickerKind};
+use crate::preview;
+use crate::projects::{split_key_value, Group, Project};
 
 pub struct ProjectItem {
     pub path: PathBuf,
     pub display: String,
     pub searchable: String,
     pub exists: bool,
+    pub project: Project,
+    /// Set for group entries, whose path is the group's cd target
+    pub group: Option<Group>,
 }
 
 impl SkimItem for ProjectItem {
@@ -20,15 +28,85 @@ impl SkimItem for ProjectItem {
     fn display<'a>(&'a self, _context: DisplayContext<'a>) -> AnsiString<'a> {
         AnsiString::parse(&self.display)
     }
+
+    fn preview(&self, _context: PreviewContext) -> ItemPreview {
+        match &self.group {
+            Some(group) => ItemPreview::AnsiText(preview::render_group(group)),
+            None => ItemPreview::AnsiText(preview::render(&self.project)),
+        }
+    }
 }
 
 pub enum SelectionResult {
     Selected(PathBuf),
     MissingSelected(PathBuf),
     MultiSelected(Vec<PathBuf>),
+    Action(PickerAction, PathBuf),
+    Group(String),
     Cancelled,
 }
 
+/// Actions bound to picker keys, acting on the highlighted project
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickerAction {
+    EditorOnly,
+    CdOnly,
+    AiOnly,
+    EditTags,
+    TogglePin,
+    Remove,
+    FileManager,
+    CopyPath,
+    RunProfile,
+}
+
+impl PickerAction {
+    pub const ALL: [PickerAction; 9] = [
+        PickerAction::EditorOnly,
+        PickerAction::CdOnly,
+        PickerAction::AiOnly,
+        PickerAction::EditTags,
+        PickerAction::TogglePin,
+        PickerAction::Remove,
+        PickerAction::FileManager,
+        PickerAction::CopyPath,
+        PickerAction::RunProfile,
+    ];
+
+    /// Name passed through skim's `accept(<name>)` binding
+    pub fn name(self) -> &'static str {
+        match self {
+            PickerAction::EditorOnly => "editor",
+            PickerAction::CdOnly => "cd",
+            PickerAction::AiOnly => "ai",
+            PickerAction::EditTags => "tags",
+            PickerAction::TogglePin => "pin",
+            PickerAction::Remove => "remove",
+            PickerAction::FileManager => "files",
+            PickerAction::CopyPath => "copy",
+            PickerAction::RunProfile => "run",
+        }
+    }
+
+    pub fn key(self, keys: &KeyBindings) -> &str {
+        match self {
+            PickerAction::EditorOnly => &keys.editor,
+            PickerAction::CdOnly => &keys.cd,
+            PickerAction::AiOnly => &keys.ai,
+            PickerAction::EditTags => &keys.tags,
+            PickerAction::TogglePin => &keys.pin,
+            PickerAction::Remove => &keys.remove,
+            PickerAction::FileManager => &keys.file_manager,
+            PickerAction::CopyPath => &keys.copy_path,
+            PickerAction::RunProfile => &keys.profile,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
 /// Check if filter should be case-sensitive (smart-case: sensitive if contains uppercase)
 fn is_case_sensitive(filter: &str) -> bool {
     filter.chars().any(|c| c.is_uppercase())
@@ -59,139 +137,366 @@ pub fn fuzzy_match(text: &str, pattern: &str) -> bool {
     pattern_chars.peek().is_none()
 }
 
+/// Match a `key:value` filter term against a project's `key=value` tags.
+/// Returns `None` when the term is not a tag query: `key` is not a tag key
+/// of any project in `keys`.
+fn match_key_value(project: &Project, filter: &str, keys: &HashSet<String>) -> Option<bool> {
+    let (key, value) = filter.split_once(':')?;
+    let key = key.to_lowercase();
+    if !keys.contains(&key) {
+        return None;
+    }
+    let values = project.tag_values(&key);
+    Some(values.iter().any(|v| fuzzy_match(v, value)))
+}
+
 /// Filter projects by multiple terms (AND logic)
 pub fn filter_projects<'a>(projects: &[&'a Project], filters: &[String]) -> Vec<&'a Project> {
     if filters.is_empty() {
         return projects.to_vec();
     }
 
+    let keys: HashSet<String> = projects
+        .iter()
+        .flat_map(|p| p.effective_tags())
+        .filter_map(split_key_value)
+        .map(|(key, _)| key.to_string())
+        .collect();
+
     projects
         .iter()
         .filter(|project| {
-            // Build searchable text: path + tags
-            let path_str = project.path.display().to_string();
-            let tags_str = project.tags.join(" ");
-            let searchable = format!("{} {}", path_str, tags_str);
+            let searchable = project.searchable_text();
 
-            // All filter terms must match (AND logic)
-            filters.iter().all(|filter| fuzzy_match(&searchable, filter))
+            // All filter terms must match (AND logic); `key:value` terms
+            // only match tags with that key
+            filters.iter().all(|filter| {
+                match_key_value(project, filter, &keys)
+                    .unwrap_or_else(|| fuzzy_match(&searchable, filter))
+            })
         })
         .copied()
         .collect()
 }
 
-fn create_project_items(projects: &[&Project]) -> Vec<ProjectItem> {
+/// Git status columns shown in front of picker rows, padded so they line up;
+/// projects without a status get blank columns
+fn git_columns(
+    projects: &[&Project],
+    statuses: &HashMap<PathBuf, RepoStatus>,
+) -> HashMap<PathBuf, String> {
+    let rows: Vec<(&PathBuf, [String; 3])> = projects
+        .iter()
+        .map(|p| {
+            let columns = match statuses.get(&p.path) {
+                Some(status) => [
+                    status.branch.clone(),
+                    status.state(),
+                    status.last_commit_age(),
+                ],
+                None => Default::default(),
+            };
+            (&p.path, columns)
+        })
+        .collect();
+
+    let mut widths = [0; 3];
+    for (_, columns) in &rows {
+        for (width, column) in widths.iter_mut().zip(columns) {
+            *width = (*width).max(column.chars().count());
+        }
+    }
+
+    rows.into_iter()
+        .map(|(path, [branch, state, age])| {
+            let column = format!(
+                "\x1b[2m{:<bw$}  {:<sw$}  {:>aw$}\x1b[0m  ",
+                branch,
+                state,
+                age,
+                bw = widths[0],
+                sw = widths[1],
+                aw = widths[2]
+            );
+            (path.clone(), column)
+        })
+        .collect()
+}
+
+fn create_project_items(
+    projects: &[&Project],
+    statuses: Option<&HashMap<PathBuf, RepoStatus>>,
+) -> Vec<Arc<ProjectItem>> {
+    let git_columns = statuses.map(|s| git_columns(projects, s));
+
     projects
         .iter()
         .map(|p| {
             let exists = p.exists();
-            let tags_str = if p.tags.is_empty() {
+            let tags_display = p.tags_display();
+            let tags_str = if tags_display.is_empty() {
                 String::new()
             } else {
-                format!(" [{}]", p.tags.join(", "))
+                format!(" [{}]", tags_display)
             };
             let missing_indicator = if exists { "" } else { " [MISSING]" };
-            let display = format!("{}{}{}", p.path.display(), tags_str, missing_indicator);
+            let pin_indicator = if p.pinned { "★ " } else { "" };
+            let mut display = match &p.branch {
+                Some(branch) => format!(
+                    "  ↳ {} ({}){}{}",
+                    p.path.display(),
+                    branch,
+                    tags_str,
+                    missing_indicator
+                ),
+                None => format!(
+                    "{}{}{}{}",
+                    pin_indicator,
+                    p.path.display(),
+                    tags_str,
+                    missing_indicator
+                ),
+            };
+            if let Some(column) = git_columns.as_ref().and_then(|c| c.get(&p.path)) {
+                display.insert_str(0, column);
+            }
 
-            // Searchable text includes path and tags for matching
-            let searchable = format!("{} {}", p.path.display(), p.tags.join(" "));
+            let searchable = p.searchable_text();
 
-            ProjectItem {
+            Arc::new(ProjectItem {
                 path: p.path.clone(),
                 display,
                 searchable,
                 exists,
-            }
+                project: (*p).clone(),
+                group: None,
+            })
+        })
+        .collect()
+}
+
+/// Group entries, listed before projects
+fn create_group_items(groups: &[&Group]) -> Vec<Arc<ProjectItem>> {
+    groups
+        .iter()
+        .map(|g| {
+            let names: Vec<String> = g
+                .projects
+                .iter()
+                .map(|p| {
+                    p.file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| p.display().to_string())
+                })
+                .collect();
+            let path = g.cd_target();
+            Arc::new(ProjectItem {
+                display: format!("⧉ {} ({})", g.name, names.join(", ")),
+                searchable: g.searchable_text(),
+                exists: true,
+                project: Project::new(path.clone()),
+                path,
+                group: Some((*g).clone()),
+            })
         })
         .collect()
 }
 
 pub fn select_project(
     projects: &[&Project],
+    groups: &[&Group],
     query: Option<&str>,
+    config: &Config,
 ) -> Result<SelectionResult> {
-    let items = create_project_items(projects);
+    let statuses = config.git_status.then(|| {
+        let paths: Vec<PathBuf> = projects.iter().map(|p| p.path.clone()).collect();
+        git::repo_statuses(&paths, config.git_status_timeout())
+    });
+    let mut items = create_group_items(groups);
+    items.extend(create_project_items(projects, statuses.as_ref()));
 
-    let items: Vec<Arc<dyn SkimItem>> = items
-        .into_iter()
-        .map(|item| Arc::new(item) as Arc<dyn SkimItem>)
-        .collect();
+    // Action keys act on projects; on a group they return to the picker
+    let picked = loop {
+        let Some(picked) = pick(&items, query, config, true, true)? else {
+            return Ok(SelectionResult::Cancelled);
+        };
+        match (&items[picked.indices[0]].group, picked.action) {
+            (Some(group), Some(action)) => eprintln!(
+                "The {} action does not apply to groups: {}",
+                action.name(),
+                group.name
+            ),
+            _ => break picked,
+        }
+    };
 
-    let mut options_builder = SkimOptionsBuilder::default();
-    options_builder.height(Some("100%")).multi(false);
+    // Several marked projects are opened together; action keys act on the
+    // first one
+    if picked.indices.len() > 1 && picked.action.is_none() {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for &index in &picked.indices {
+            let item = &items[index];
+            if let Some(group) = &item.group {
+                // A marked group contributes its members
+                for member in &group.projects {
+                    if member.exists() && !paths.contains(member) {
+                        paths.push(member.clone());
+                    }
+                }
+            } else if paths.contains(&item.path) {
+                continue;
+            } else if item.exists {
+                paths.push(item.path.clone());
+            } else {
+                eprintln!("Skipping missing project: {}", item.path.display());
+            }
+        }
+        return Ok(match paths.len() {
+            0 => SelectionResult::Cancelled,
+            1 => SelectionResult::Selected(paths.remove(0)),
+            _ => SelectionResult::MultiSelected(paths),
+        });
+    }
 
-    if let Some(q) = query {
-        options_builder.query(Some(q));
+    let item = &items[picked.indices[0]];
+    if let Some(group) = &item.group {
+        return Ok(SelectionResult::Group(group.name.clone()));
     }
+    match picked.action {
+        // Removing is the one action that makes sense for a missing project
+        Some(action) if item.exists || action == PickerAction::Remove => {
+            Ok(SelectionResult::Action(action, item.path.clone()))
+        }
+        _ if item.exists => Ok(SelectionResult::Selected(item.path.clone())),
+        _ => Ok(SelectionResult::MissingSelected(item.path.clone())),
+    }
+}
 
-    let options = options_builder.build().unwrap();
+pub fn select_projects_multi(projects: &[&Project], config: &Config) -> Result<SelectionResult> {
+    let items = create_project_items(projects, None);
 
-    let (tx, rx): (SkimItemSender, SkimItemReceiver) = unbounded();
-    for item in items {
-        let _ = tx.send(item);
-    }
-    drop(tx);
+    let Some(picked) = pick(&items, None, config, true, false)? else {
+        return Ok(SelectionResult::Cancelled);
+    };
+    let paths = picked
+        .indices
+        .iter()
+        .map(|&index| items[index].path.clone())
+        .collect();
+    Ok(SelectionResult::MultiSelected(paths))
+}
 
-    let output = Skim::run_with(&options, Some(rx));
+/// Run the configured picker backend over the items, with the preview and
+/// action keys when `actions` is set
+fn pick(
+    items: &[Arc<ProjectItem>],
+    query: Option<&str>,
+    config: &Config,
+    multi: bool,
+    actions: bool,
+) -> Result<Option<Picked>> {
+    let header = if actions {
+        let mut hints = vec!["tab: mark".to_string()];
+        hints.extend(
+            PickerAction::ALL
+                .into_iter()
+                .map(|action| (action.key(&config.keys), action.name()))
+                .filter(|(key, _)| !key.is_empty())
+                .map(|(key, name)| format!("{}: {}", key, name)),
+        );
+        hints.join(" | ")
+    } else {
+        "Tab: select | Enter: confirm | Esc: cancel".to_string()
+    };
 
-    match output {
-        Some(out) if out.is_abort => Ok(SelectionResult::Cancelled),
-        Some(out) if !out.selected_items.is_empty() => {
-            let selected = &out.selected_items[0];
-            let item = (**selected)
-                .as_any()
-                .downcast_ref::<ProjectItem>()
-                .unwrap();
-            if item.exists {
-                Ok(SelectionResult::Selected(item.path.clone()))
-            } else {
-                Ok(SelectionResult::MissingSelected(item.path.clone()))
-            }
+    let paths: Vec<String> = items.iter().map(|i| i.path.display().to_string()).collect();
+    let lines: Vec<Line> = items
+        .iter()
+        .zip(&paths)
+        .map(|(item, path)| Line {
+            path,
+            group: item.group.as_ref().map(|g| g.name.as_str()),
+            display: &item.display,
+        })
+        .collect();
+
+    match picker::resolve(config.picker) {
+        PickerKind::Fzf => picker::fzf(&lines, query, config, multi, actions, &header),
+        PickerKind::Plain => picker::plain(&lines, multi, actions),
+        PickerKind::Skim | PickerKind::Auto => {
+            skim(items, query, config, multi, actions, &header)
         }
-        _ => Ok(SelectionResult::Cancelled),
     }
 }
 
-pub fn select_projects_multi(projects: &[&Project]) -> Result<SelectionResult> {
-    let items = create_project_items(projects);
+fn skim(
+    items: &[Arc<ProjectItem>],
+    query: Option<&str>,
+    config: &Config,
+    multi: bool,
+    actions: bool,
+    header: &str,
+) -> Result<Option<Picked>> {
+    let mut options_builder = SkimOptionsBuilder::default();
+    options_builder.height(Some("100%")).multi(multi);
 
-    let items: Vec<Arc<dyn SkimItem>> = items
-        .into_iter()
-        .map(|item| Arc::new(item) as Arc<dyn SkimItem>)
-        .collect();
+    // Previews are rendered by the items themselves; an empty command just
+    // enables the preview window
+    let preview_window = if config.preview {
+        config.preview_window.clone()
+    } else {
+        format!("{}:hidden", config.preview_window)
+    };
+    let mut bindings = Vec::new();
+    if actions {
+        bindings.push(format!("{}:toggle-preview", config.preview_toggle_key));
+        for action in PickerAction::ALL {
+            let key = action.key(&config.keys);
+            if !key.is_empty() {
+                bindings.push(format!("{}:accept({})", key, action.name()));
+            }
+        }
+        options_builder
+            .preview(Some(""))
+            .preview_window(Some(&preview_window));
+    }
+    let bindings: Vec<&str> = bindings.iter().map(|b| b.as_str()).collect();
+    options_builder.bind(bindings);
+    if !header.is_empty() {
+        options_builder.header(Some(header));
+    }
 
-    let options = SkimOptionsBuilder::default()
-        .height(Some("100%"))
-        .multi(true)
-        .header(Some("Tab: select | Enter: confirm | Esc: cancel"))
-        .build()
-        .unwrap();
+    if let Some(q) = query {
+        options_builder.query(Some(q));
+    }
+
+    let options = options_builder.build().unwrap();
 
     let (tx, rx): (SkimItemSender, SkimItemReceiver) = unbounded();
     for item in items {
-        let _ = tx.send(item);
+        let _ = tx.send(Arc::clone(item) as Arc<dyn SkimItem>);
     }
     drop(tx);
 
     let output = Skim::run_with(&options, Some(rx));
 
     match output {
-        Some(out) if out.is_abort => Ok(SelectionResult::Cancelled),
+        Some(out) if out.is_abort => Ok(None),
         Some(out) if !out.selected_items.is_empty() => {
-            let paths: Vec<PathBuf> = out
+            let indices = out
                 .selected_items
                 .iter()
-                .map(|selected| {
-                    (**selected)
-                        .as_any()
-                        .downcast_ref::<ProjectItem>()
-                        .unwrap()
-                        .path
-                        .clone()
+                .filter_map(|selected| {
+                    let path = &(**selected).as_any().downcast_ref::<ProjectItem>()?.path;
+                    items.iter().position(|item| &item.path == path)
                 })
                 .collect();
-            Ok(SelectionResult::MultiSelected(paths))
+            let action = match &out.final_event {
+                Event::EvActAccept(Some(name)) => PickerAction::from_name(name),
+                _ => None,
+            };
+            Ok(Some(Picked { indices, action }))
         }
-        _ => Ok(SelectionResult::Cancelled),
+        _ => Ok(None),
     }
 }
@@ -0,0 +1,149 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::glob::{expand_dirs, matches_path};
+
+/// Member directories declared by the workspace manifests in `root`:
+/// Cargo `[workspace] members`, `pnpm-workspace.yaml`, `package.json`
+/// workspaces and `go.work`
+pub fn members(root: &Path) -> Vec<PathBuf> {
+    let mut includes: Vec<String> = Vec::new();
+    let mut excludes: Vec<String> = Vec::new();
+
+    cargo_patterns(root, &mut includes, &mut excludes);
+    pnpm_patterns(root, &mut includes, &mut excludes);
+    npm_patterns(root, &mut includes, &mut excludes);
+    go_work_patterns(root, &mut includes);
+
+    let mut members: Vec<PathBuf> = includes
+        .iter()
+        .flat_map(|pattern| expand_dirs(root, pattern))
+        .filter(|dir| dir != root)
+        .filter(|dir| {
+            let relative = dir.strip_prefix(root).unwrap_or(dir);
+            !excludes.iter().any(|pattern| matches_path(pattern, relative))
+        })
+        .map(|dir| dir.canonicalize().unwrap_or(dir))
+        .collect();
+    members.sort();
+    members.dedup();
+    members
+}
+
+fn cargo_patterns(root: &Path, includes: &mut Vec<String>, excludes: &mut Vec<String>) {
+    let Ok(content) = fs::read_to_string(root.join("Cargo.toml")) else {
+        return;
+    };
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        return;
+    };
+    let Some(workspace) = manifest.get("workspace").and_then(|w| w.as_table()) else {
+        return;
+    };
+
+    let strings = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    includes.extend(strings("members"));
+    excludes.extend(strings("exclude"));
+}
+
+fn pnpm_patterns(root: &Path, includes: &mut Vec<String>, excludes: &mut Vec<String>) {
+    let Ok(content) = fs::read_to_string(root.join("pnpm-workspace.yaml")) else {
+        return;
+    };
+
+    // Only the `packages:` list is needed, so read it line by line instead of
+    // pulling in a YAML parser
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed.starts_with("packages:");
+            continue;
+        }
+        if !in_packages {
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-') {
+            let item = item.trim().trim_matches(|c| c == '\'' || c == '"');
+            match item.strip_prefix('!') {
+                Some(excluded) => excludes.push(excluded.to_string()),
+                None => includes.push(item.to_string()),
+            }
+        }
+    }
+}
+
+fn npm_patterns(root: &Path, includes: &mut Vec<String>, excludes: &mut Vec<String>) {
+    let Ok(content) = fs::read_to_string(root.join("package.json")) else {
+        return;
+    };
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return;
+    };
+
+    // `workspaces` is either an array or `{ "packages": [...] }` (yarn)
+    let workspaces = match manifest.get("workspaces") {
+        Some(serde_json::Value::Object(obj)) => obj.get("packages"),
+        other => other,
+    };
+    let Some(patterns) = workspaces.and_then(|w| w.as_array()) else {
+        return;
+    };
+
+    for pattern in patterns.iter().filter_map(|p| p.as_str()) {
+        match pattern.strip_prefix('!') {
+            Some(excluded) => excludes.push(excluded.to_string()),
+            None => includes.push(pattern.to_string()),
+        }
+    }
+}
+
+fn go_work_patterns(root: &Path, includes: &mut Vec<String>) {
+    let Ok(content) = fs::read_to_string(root.join("go.work")) else {
+        return;
+    };
+
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                includes.push(unquote(line).to_string());
+            }
+        } else if let Some(rest) = line
+            .strip_prefix("use")
+            .filter(|rest| rest.starts_with([' ', '\t', '(']))
+        {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                includes.push(unquote(rest).to_string());
+            }
+        }
+    }
+}
+
+/// Strip the double quotes or backquotes around a go.work path
+fn unquote(path: &str) -> &str {
+    ['"', '`']
+        .into_iter()
+        .find_map(|quote| path.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(path)
+}